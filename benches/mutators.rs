@@ -0,0 +1,31 @@
+use hantu::{MutationEngine, TestCase};
+use std::sync::Arc;
+use std::time::Instant;
+
+const ITERATIONS: usize = 100_000;
+
+fn main() {
+    let corpus: Arc<Vec<Vec<u8>>> = Arc::new(vec![vec![0x41; 4096], vec![0x42; 1024]]);
+    let token_dict = vec!["foobar".to_string(), "deadbeefcafebabe".to_string()];
+    let mut mutation_engine = MutationEngine::new(
+        Some(TestCase::new(&corpus[0])),
+        Some(0x1337),
+        Some(token_dict),
+        Some(corpus.clone()),
+    );
+    let all = mutation_engine.effective_mutators().to_vec();
+
+    for mutator in all {
+        // Restrict the engine to a single mutator so each run times only that one
+        mutation_engine.set_mutators(vec![mutator.clone()]).unwrap();
+        let now = Instant::now();
+        for _ in 0..ITERATIONS {
+            let _out = mutation_engine.apply_all_mutators_once();
+        }
+        println!(
+            "{:<24} {:12.1} execs/s",
+            format!("{:?}", mutator),
+            ITERATIONS as f64 / now.elapsed().as_secs_f64()
+        );
+    }
+}
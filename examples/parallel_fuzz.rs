@@ -0,0 +1,45 @@
+use hantu::{MutationEngine, Rng};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+const WORKERS: usize = 4;
+const TOTAL_MUTATIONS: usize = 100_000;
+
+fn main() {
+    let corpus: Arc<Vec<Vec<u8>>> = Arc::new(vec![
+        b"GET /index.html HTTP/1.1\r\nHost: localhost\r\n\r\n".to_vec(),
+        b"POST /upload HTTP/1.1\r\nContent-Length: 4\r\n\r\nABCD".to_vec(),
+    ]);
+    let token_dict = vec!["HTTP/1.1".to_string(), "\r\n".to_string()];
+    let results: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::with_capacity(TOTAL_MUTATIONS));
+
+    // Fork one seed per worker from a single parent RNG
+    let mut parent = Rng::new(0);
+    let seeds: Vec<usize> = (0..WORKERS).map(|_| parent.rand() | 1).collect();
+
+    let now = Instant::now();
+    rayon::scope(|s| {
+        for seed in seeds {
+            let corpus = Arc::clone(&corpus);
+            let token_dict = token_dict.clone();
+            let results = &results;
+            s.spawn(move |_| {
+                let mut mutation_engine =
+                    MutationEngine::new(None, Some(seed), Some(token_dict), Some(corpus));
+                let mut local = Vec::with_capacity(TOTAL_MUTATIONS / WORKERS);
+                for _ in 0..TOTAL_MUTATIONS / WORKERS {
+                    local.push(mutation_engine.mutate().clone());
+                }
+                results.lock().unwrap().append(&mut local);
+            });
+        }
+    });
+
+    let results = results.into_inner().unwrap();
+    println!(
+        "Collected {} test cases from {} workers in {:.2}s",
+        results.len(),
+        WORKERS,
+        now.elapsed().as_secs_f64()
+    );
+}
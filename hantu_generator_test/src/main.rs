@@ -53,7 +53,8 @@ fn main() {
     println!("Average test case size: {avg_tc_sz} bytes");
     let token_dict = vec!["foobar".to_string(), "deadbeefcafebabe".to_string()];
 
-    let mut mutation_engine = MutationEngine::new(None, None, Some(token_dict), Some(corpus));
+    let seed = MutationEngine::seed_from_entropy();
+    let mut mutation_engine = MutationEngine::new(None, Some(seed), Some(token_dict), Some(corpus));
     let now = Instant::now();
     let mut i: usize = 0;
     loop {
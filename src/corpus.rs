@@ -0,0 +1,647 @@
+use crate::Rng;
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io;
+#[cfg(feature = "zip")]
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Shannon entropy of `data` in bits per byte, between 0.0 and 8.0.
+pub fn shannon_entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    data.iter().for_each(|&b| counts[b as usize] += 1);
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Size distribution of a corpus, as returned by `Corpus::stats`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CorpusStats {
+    pub count: usize,
+    pub total_bytes: usize,
+    pub min_len: usize,
+    pub max_len: usize,
+    pub mean_len: f64,
+    pub median_len: usize,
+    pub std_dev: f64,
+}
+
+impl fmt::Display for CorpusStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} entries, {} bytes, len min {} / max {} / mean {:.2} / median {} / std dev {:.2}",
+            self.count,
+            self.total_bytes,
+            self.min_len,
+            self.max_len,
+            self.mean_len,
+            self.median_len,
+            self.std_dev
+        )
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Corpus {
+    entries: Vec<Vec<u8>>,
+    // Generation of each entry, parallel to `entries`; older entries have lower numbers
+    generations: Vec<u64>,
+    next_generation: u64,
+}
+
+impl Corpus {
+    pub fn new(entries: Vec<Vec<u8>>) -> Self {
+        let next_generation = entries.len() as u64;
+        Corpus {
+            generations: (0..next_generation).collect(),
+            entries,
+            next_generation,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push(&mut self, entry: Vec<u8>) {
+        self.entries.push(entry);
+        self.generations.push(self.next_generation);
+        self.next_generation += 1;
+    }
+
+    /// Generation the entry at `idx` was added in, counting up from 0 per corpus.
+    pub fn generation(&self, idx: usize) -> Option<u64> {
+        self.generations.get(idx).copied()
+    }
+
+    // Keeps the entries whose flag in `keep` is set, along with their generations
+    fn retain_mask(&mut self, keep: &[bool]) {
+        let mut idx = 0;
+        self.entries.retain(|_| {
+            idx += 1;
+            keep[idx - 1]
+        });
+        let mut idx = 0;
+        self.generations.retain(|_| {
+            idx += 1;
+            keep[idx - 1]
+        });
+    }
+
+    // Reorders entries and generations so that position i holds old entry `order[i]`
+    fn reorder(&mut self, order: &[usize]) {
+        let mut entries: Vec<Option<Vec<u8>>> = self.entries.drain(..).map(Some).collect();
+        self.entries = order.iter().map(|&i| entries[i].take().unwrap()).collect();
+        self.generations = order.iter().map(|&i| self.generations[i]).collect();
+    }
+
+    pub fn entries(&self) -> &[Vec<u8>] {
+        &self.entries
+    }
+
+    /// Iterates over the entries in insertion order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(self.entries.iter())
+    }
+
+    /// Returns true if an identical entry is already in the corpus.
+    pub fn contains(&self, entry: &[u8]) -> bool {
+        self.entries.iter().any(|e| e.as_slice() == entry)
+    }
+
+    /// Loads every file in `path` that is not already in the corpus. Returns the number
+    /// of newly added entries, so it can be polled to pick up inputs from other workers.
+    pub fn merge_from_dir(&mut self, path: &Path) -> Result<usize, io::Error> {
+        let mut files = Vec::new();
+        for dir_entry in fs::read_dir(path)? {
+            let file = dir_entry?.path();
+            if file.is_file() {
+                files.push(file);
+            }
+        }
+        files.sort();
+        let loaded = files.iter().map(fs::read).collect::<Result<Vec<_>, _>>()?;
+
+        // Compare content rather than a hash so colliding inputs are both kept
+        let mut seen: HashSet<&[u8]> = self.entries.iter().map(|e| e.as_slice()).collect();
+        let keep: Vec<bool> = loaded.iter().map(|d| seen.insert(d.as_slice())).collect();
+        let before = self.entries.len();
+        for (data, keep) in loaded.into_iter().zip(keep) {
+            if keep {
+                self.push(data);
+            }
+        }
+        Ok(self.entries.len() - before)
+    }
+
+    /// Writes every entry into a ZIP archive at `path`, one numbered file per entry.
+    #[cfg(feature = "zip")]
+    pub fn export_as_zip(&self, path: &Path) -> Result<(), io::Error> {
+        let mut archive = zip::ZipWriter::new(fs::File::create(path)?);
+        let options = zip::write::SimpleFileOptions::default();
+        for (id, entry) in self.entries.iter().enumerate() {
+            archive.start_file(format!("{id:06}"), options)?;
+            archive.write_all(entry)?;
+        }
+        archive.finish()?;
+        Ok(())
+    }
+
+    /// Appends every file in the ZIP archive at `path`, in archive order. Returns the
+    /// number of entries added.
+    #[cfg(feature = "zip")]
+    pub fn import_from_zip(&mut self, path: &Path) -> Result<usize, io::Error> {
+        let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+        let before = self.entries.len();
+        for idx in 0..archive.len() {
+            let mut file = archive.by_index(idx)?;
+            if file.is_dir() {
+                continue;
+            }
+            // The header size is untrusted, so let the buffer grow with the real content
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            self.push(data);
+        }
+        Ok(self.entries.len() - before)
+    }
+
+    /// Jaccard similarity of the 4-byte n-grams of `a` and `b`, from 0.0 (nothing shared)
+    /// to 1.0. Inputs too short to hold a 4-gram only score 1.0 when equal.
+    pub fn similarity_score(a: &[u8], b: &[u8]) -> f64 {
+        let grams_a: HashSet<&[u8]> = a.windows(4).collect();
+        let grams_b: HashSet<&[u8]> = b.windows(4).collect();
+        let union = grams_a.union(&grams_b).count();
+        if union == 0 {
+            return if a == b { 1.0 } else { 0.0 };
+        }
+        grams_a.intersection(&grams_b).count() as f64 / union as f64
+    }
+
+    /// Sum of all entry lengths in bytes.
+    pub fn total_bytes(&self) -> usize {
+        self.entries.iter().map(|e| e.len()).sum()
+    }
+
+    /// Entry length statistics; all zero for an empty corpus.
+    pub fn stats(&self) -> CorpusStats {
+        if self.entries.is_empty() {
+            return CorpusStats::default();
+        }
+        let mut lens: Vec<usize> = self.entries.iter().map(|e| e.len()).collect();
+        lens.sort_unstable();
+        let count = lens.len();
+        let total_bytes: usize = lens.iter().sum();
+        let mean_len = total_bytes as f64 / count as f64;
+        let variance = lens
+            .iter()
+            .map(|&l| (l as f64 - mean_len).powi(2))
+            .sum::<f64>()
+            / count as f64;
+        CorpusStats {
+            count,
+            total_bytes,
+            min_len: lens[0],
+            max_len: lens[count - 1],
+            mean_len,
+            median_len: lens[(count - 1) / 2],
+            std_dev: variance.sqrt(),
+        }
+    }
+
+    /// Removes entries whose content already appeared earlier in the corpus.
+    /// Returns the number of removed duplicates.
+    pub fn deduplicate(&mut self) -> usize {
+        let before = self.entries.len();
+        let mut seen: HashSet<&[u8]> = HashSet::new();
+        let keep: Vec<bool> = self
+            .entries
+            .iter()
+            .map(|e| seen.insert(e.as_slice()))
+            .collect();
+        self.retain_mask(&keep);
+        before - self.entries.len()
+    }
+
+    /// Removes the largest entries until the corpus holds at most `max_bytes`.
+    /// Returns the number of removed entries.
+    pub fn trim_to_max_size(&mut self, max_bytes: usize) -> usize {
+        let mut total = self.total_bytes();
+        if total <= max_bytes {
+            return 0;
+        }
+        let mut by_size: Vec<usize> = (0..self.entries.len()).collect();
+        by_size.sort_by_key(|&i| std::cmp::Reverse(self.entries[i].len()));
+        let mut keep = vec![true; self.entries.len()];
+        for i in by_size {
+            if total <= max_bytes {
+                break;
+            }
+            total -= self.entries[i].len();
+            keep[i] = false;
+        }
+        self.retain_mask(&keep);
+        keep.iter().filter(|&&k| !k).count()
+    }
+
+    /// Removes the oldest entries until at most `max` remain.
+    /// Returns the number of removed entries.
+    pub fn trim_to_max_entries(&mut self, max: usize) -> usize {
+        self.evict_oldest(self.entries.len().saturating_sub(max))
+    }
+
+    /// Removes the `n` entries with the lowest generation, i.e. the ones added first.
+    /// Returns the number of removed entries.
+    pub fn evict_oldest(&mut self, n: usize) -> usize {
+        let n = n.min(self.entries.len());
+        let mut by_age: Vec<usize> = (0..self.entries.len()).collect();
+        by_age.sort_by_key(|&i| self.generations[i]);
+        let mut keep = vec![true; self.entries.len()];
+        for &i in &by_age[..n] {
+            keep[i] = false;
+        }
+        self.retain_mask(&keep);
+        n
+    }
+
+    /// Orders entries from shortest to longest.
+    pub fn sort_by_length(&mut self) {
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.sort_by_key(|&i| self.entries[i].len());
+        self.reorder(&order);
+    }
+
+    /// Orders entries by Shannon entropy, least structured (highest entropy) first.
+    pub fn sort_by_entropy(&mut self) {
+        let scores: Vec<f64> = self.entries.iter().map(|e| shannon_entropy(e)).collect();
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        self.reorder(&order);
+    }
+
+    /// Picks a random entry whose length is within 50% of `target`, falling back to the
+    /// entry closest in length if none qualifies.
+    pub fn random_entry_of_length_near(&self, target: usize, prng: &mut Rng) -> Option<&[u8]> {
+        let slack = target / 2;
+        let near: Vec<&Vec<u8>> = self
+            .entries
+            .iter()
+            .filter(|e| e.len().abs_diff(target) <= slack)
+            .collect();
+        if near.is_empty() {
+            self.entries
+                .iter()
+                .min_by_key(|e| e.len().abs_diff(target))
+                .map(|e| e.as_slice())
+        } else {
+            Some(near[prng.rand() % near.len()])
+        }
+    }
+
+    /// Picks two entries at distinct indices; their contents may still be equal. Returns
+    /// `None` if the corpus has fewer than two entries.
+    pub fn random_pair(&self, prng: &mut Rng) -> Option<(&[u8], &[u8])> {
+        let len = self.entries.len();
+        if len < 2 {
+            return None;
+        }
+        let a = prng.rand() % len;
+        let mut b = prng.rand() % (len - 1);
+        if b >= a {
+            b += 1;
+        }
+        Some((&self.entries[a], &self.entries[b]))
+    }
+
+    /// Picks a random entry starting with `prefix`, falling back to any entry if none match.
+    pub fn sample_by_prefix(&self, prefix: &[u8], prng: &mut Rng) -> Option<&[u8]> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let matches: Vec<&Vec<u8>> = self
+            .entries
+            .iter()
+            .filter(|e| e.starts_with(prefix))
+            .collect();
+        if matches.is_empty() {
+            Some(&self.entries[prng.rand() % self.entries.len()])
+        } else {
+            Some(matches[prng.rand() % matches.len()])
+        }
+    }
+}
+
+/// Iterator over corpus entries, created by `Corpus::iter`.
+#[derive(Debug, Clone)]
+pub struct Iter<'a>(std::slice::Iter<'a, Vec<u8>>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        self.0.next().map(|e| e.as_slice())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|e| e.as_slice())
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl<'a> IntoIterator for &'a Corpus {
+    type Item = &'a [u8];
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl From<Vec<Vec<u8>>> for Corpus {
+    fn from(entries: Vec<Vec<u8>>) -> Self {
+        Corpus::new(entries)
+    }
+}
+
+impl From<Corpus> for Arc<Vec<Vec<u8>>> {
+    fn from(corpus: Corpus) -> Self {
+        Arc::new(corpus.entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http_corpus() -> Corpus {
+        Corpus::new(vec![
+            b"GET / HTTP/1.1".to_vec(),
+            b"POST /upload HTTP/1.1".to_vec(),
+            b"GET /index.html HTTP/1.0".to_vec(),
+        ])
+    }
+
+    #[test]
+    fn contains_checks_for_identical_entries() {
+        assert!(!Corpus::default().contains(b""));
+        let corpus = Corpus::new(vec![b"GET / HTTP/1.1".to_vec()]);
+        assert!(corpus.contains(b"GET / HTTP/1.1"));
+        assert!(!corpus.contains(b"GET / HTTP/1.0"));
+        assert!(!corpus.contains(b"GET /"));
+    }
+
+    #[test]
+    fn merge_from_dir_skips_known_entries() {
+        let dir = std::env::temp_dir().join(format!("hantu-merge-{}", std::process::id()));
+        fs::create_dir_all(dir.join("subdir")).unwrap();
+        fs::write(dir.join("a"), b"GET / HTTP/1.1").unwrap();
+        fs::write(dir.join("b"), b"PUT / HTTP/1.1").unwrap();
+        fs::write(dir.join("c"), b"PUT / HTTP/1.1").unwrap();
+
+        let mut corpus = Corpus::new(vec![b"GET / HTTP/1.1".to_vec()]);
+        assert_eq!(corpus.merge_from_dir(&dir).unwrap(), 1);
+        assert_eq!(corpus.len(), 2);
+        assert!(corpus.contains(b"PUT / HTTP/1.1"));
+        assert_eq!(corpus.merge_from_dir(&dir).unwrap(), 0);
+
+        fs::write(dir.join("d"), b"DELETE / HTTP/1.1").unwrap();
+        assert_eq!(corpus.merge_from_dir(&dir).unwrap(), 1);
+        assert_eq!(corpus.len(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(corpus.merge_from_dir(&dir).is_err());
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn zip_export_round_trips() {
+        let path = std::env::temp_dir().join(format!("hantu-zip-{}.zip", std::process::id()));
+        let corpus = Corpus::new(vec![
+            b"GET / HTTP/1.1".to_vec(),
+            Vec::new(),
+            b"PUT / HTTP/1.1".to_vec(),
+        ]);
+        corpus.export_as_zip(&path).unwrap();
+
+        let mut imported = Corpus::new(vec![b"seed".to_vec()]);
+        assert_eq!(imported.import_from_zip(&path).unwrap(), 3);
+        assert_eq!(imported.entries()[0], b"seed");
+        assert_eq!(imported.entries()[1..], *corpus.entries());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn similarity_score_is_jaccard_of_4grams() {
+        assert_eq!(Corpus::similarity_score(b"GET / HTTP", b"GET / HTTP"), 1.0);
+        assert_eq!(Corpus::similarity_score(b"AAAAAAAA", b"BBBBBBBB"), 0.0);
+        // {ABCD, BCDE} vs {BCDE, CDEF}
+        assert_eq!(Corpus::similarity_score(b"ABCDE", b"BCDEF"), 1.0 / 3.0);
+        assert_eq!(Corpus::similarity_score(b"AB", b"AB"), 1.0);
+        assert_eq!(Corpus::similarity_score(b"AB", b""), 0.0);
+        assert_eq!(Corpus::similarity_score(b"ABCD", b"AB"), 0.0);
+    }
+
+    #[test]
+    fn stats_summarize_entry_lengths() {
+        assert_eq!(Corpus::default().stats(), CorpusStats::default());
+        let corpus = Corpus::new(vec![
+            vec![0; 2],
+            vec![0; 4],
+            vec![0; 4],
+            vec![0; 4],
+            vec![0; 5],
+            vec![0; 5],
+            vec![0; 7],
+            vec![0; 9],
+        ]);
+        let stats = corpus.stats();
+        assert_eq!(stats.count, 8);
+        assert_eq!(stats.total_bytes, 40);
+        assert_eq!(stats.min_len, 2);
+        assert_eq!(stats.max_len, 9);
+        assert_eq!(stats.mean_len, 5.0);
+        assert_eq!(stats.median_len, 4);
+        assert_eq!(stats.std_dev, 2.0);
+        assert_eq!(
+            stats.to_string(),
+            "8 entries, 40 bytes, len min 2 / max 9 / mean 5.00 / median 4 / std dev 2.00"
+        );
+    }
+
+    #[test]
+    fn iterates_in_insertion_order() {
+        let corpus = http_corpus();
+        let mut lens = Vec::new();
+        for entry in &corpus {
+            lens.push(entry.len());
+        }
+        assert_eq!(lens, vec![14, 21, 24]);
+        assert_eq!(corpus.iter().len(), 3);
+        assert_eq!(
+            corpus.iter().next_back(),
+            Some(&b"GET /index.html HTTP/1.0"[..])
+        );
+        assert_eq!(Corpus::default().iter().next(), None);
+    }
+
+    #[test]
+    fn deduplicate_keeps_first_occurrence() {
+        let mut corpus = Corpus::new(vec![
+            b"a".to_vec(),
+            b"b".to_vec(),
+            b"a".to_vec(),
+            b"c".to_vec(),
+            b"b".to_vec(),
+            b"a".to_vec(),
+        ]);
+        assert_eq!(corpus.deduplicate(), 3);
+        assert_eq!(
+            corpus.entries(),
+            &[b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]
+        );
+        assert_eq!(corpus.deduplicate(), 0);
+        assert_eq!(Corpus::default().deduplicate(), 0);
+    }
+
+    #[test]
+    fn evict_oldest_follows_generations() {
+        let mut corpus = Corpus::new(vec![vec![0; 30], vec![1; 10]]);
+        corpus.push(vec![2; 20]);
+        assert_eq!(corpus.generation(2), Some(2));
+        corpus.sort_by_length();
+        assert_eq!(corpus.generation(0), Some(1));
+
+        assert_eq!(corpus.evict_oldest(1), 1);
+        assert_eq!(corpus.entries(), &[vec![1; 10], vec![2; 20]]);
+        corpus.push(vec![3; 5]);
+        assert_eq!(corpus.generation(2), Some(3));
+        assert_eq!(corpus.evict_oldest(2), 2);
+        assert_eq!(corpus.entries(), &[vec![3; 5]]);
+        assert_eq!(corpus.evict_oldest(10), 1);
+        assert!(corpus.is_empty());
+        assert_eq!(corpus.generation(0), None);
+    }
+
+    #[test]
+    fn trim_to_max_size_drops_largest_first() {
+        let mut corpus = Corpus::new(vec![vec![0; 10], vec![1; 40], vec![2; 20], vec![3; 30]]);
+        assert_eq!(corpus.trim_to_max_size(100), 0);
+        assert_eq!(corpus.trim_to_max_size(35), 2);
+        assert_eq!(corpus.entries(), &[vec![0; 10], vec![2; 20]]);
+        assert_eq!(corpus.trim_to_max_size(0), 2);
+        assert!(corpus.is_empty());
+    }
+
+    #[test]
+    fn trim_to_max_entries_drops_oldest_first() {
+        let mut corpus = Corpus::new(vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+        assert_eq!(corpus.trim_to_max_entries(5), 0);
+        assert_eq!(corpus.trim_to_max_entries(1), 2);
+        assert_eq!(corpus.entries(), &[b"c".to_vec()]);
+    }
+
+    #[test]
+    fn sort_by_length_and_entropy() {
+        let mut corpus = Corpus::new(vec![
+            b"aaaaaaaa".to_vec(),
+            b"abcdefgh".to_vec(),
+            b"ab".to_vec(),
+            b"aabbaabbaabb".to_vec(),
+        ]);
+        corpus.sort_by_length();
+        let lens: Vec<usize> = corpus.entries().iter().map(|e| e.len()).collect();
+        assert_eq!(lens, vec![2, 8, 8, 12]);
+
+        corpus.sort_by_entropy();
+        assert_eq!(corpus.len(), 4);
+        assert_eq!(corpus.entries()[0], b"abcdefgh");
+        assert_eq!(corpus.entries()[3], b"aaaaaaaa");
+        assert_eq!(shannon_entropy(b"abcdefgh"), 3.0);
+        assert_eq!(shannon_entropy(b""), 0.0);
+    }
+
+    #[test]
+    fn random_entry_of_length_near_prefers_similar_lengths() {
+        let corpus = Corpus::new(vec![vec![0; 4], vec![1; 90], vec![2; 110], vec![3; 1000]]);
+        let mut prng = Rng::new(0);
+        for _ in 0..32 {
+            let entry = corpus.random_entry_of_length_near(100, &mut prng).unwrap();
+            assert!(entry.len() == 90 || entry.len() == 110);
+        }
+        let entry = corpus.random_entry_of_length_near(500, &mut prng).unwrap();
+        assert_eq!(entry.len(), 110);
+        assert!(Corpus::default()
+            .random_entry_of_length_near(10, &mut prng)
+            .is_none());
+    }
+
+    #[test]
+    fn sample_by_prefix_prefers_matches() {
+        let corpus = http_corpus();
+        let mut prng = Rng::new(0);
+        for _ in 0..32 {
+            let entry = corpus.sample_by_prefix(b"GET", &mut prng).unwrap();
+            assert!(entry.starts_with(b"GET"));
+        }
+        let entry = corpus.sample_by_prefix(b"POST", &mut prng).unwrap();
+        assert_eq!(entry, b"POST /upload HTTP/1.1");
+    }
+
+    #[test]
+    fn random_pair_uses_distinct_indices() {
+        let mut prng = Rng::new(0);
+        assert!(Corpus::default().random_pair(&mut prng).is_none());
+        assert!(Corpus::new(vec![b"a".to_vec()])
+            .random_pair(&mut prng)
+            .is_none());
+
+        let corpus = Corpus::new(vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+        for _ in 0..32 {
+            let (a, b) = corpus.random_pair(&mut prng).unwrap();
+            assert_ne!(a, b);
+        }
+        let twins = Corpus::new(vec![b"same".to_vec(), b"same".to_vec()]);
+        assert_eq!(
+            twins.random_pair(&mut prng),
+            Some((&b"same"[..], &b"same"[..]))
+        );
+    }
+
+    #[test]
+    fn sample_by_prefix_falls_back_to_uniform() {
+        let corpus = http_corpus();
+        let mut prng = Rng::new(0);
+        let entry = corpus.sample_by_prefix(b"DELETE", &mut prng).unwrap();
+        assert!(corpus.entries().iter().any(|e| e.as_slice() == entry));
+        assert!(Corpus::default()
+            .sample_by_prefix(b"GET", &mut prng)
+            .is_none());
+    }
+}
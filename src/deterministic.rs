@@ -0,0 +1,259 @@
+use crate::magic::{MAGIC_16, MAGIC_32, MAGIC_8};
+
+// Largest delta tried by the arithmetic stages, same as AFL's ARITH_MAX
+const ARITH_MAX: usize = 35;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeterministicStage {
+    BitFlip1,
+    BitFlip2,
+    BitFlip4,
+    ByteFlip1,
+    ByteFlip2,
+    ByteFlip4,
+    Arithmetic8,
+    Arithmetic16,
+    Arithmetic32,
+    InterestingValues8,
+    InterestingValues16,
+    InterestingValues32,
+}
+
+impl DeterministicStage {
+    pub const ALL: [DeterministicStage; 12] = [
+        DeterministicStage::BitFlip1,
+        DeterministicStage::BitFlip2,
+        DeterministicStage::BitFlip4,
+        DeterministicStage::ByteFlip1,
+        DeterministicStage::ByteFlip2,
+        DeterministicStage::ByteFlip4,
+        DeterministicStage::Arithmetic8,
+        DeterministicStage::Arithmetic16,
+        DeterministicStage::Arithmetic32,
+        DeterministicStage::InterestingValues8,
+        DeterministicStage::InterestingValues16,
+        DeterministicStage::InterestingValues32,
+    ];
+
+    // Bits for the bit flip stages, bytes for everything else
+    fn width(self) -> usize {
+        match self {
+            DeterministicStage::BitFlip1
+            | DeterministicStage::ByteFlip1
+            | DeterministicStage::Arithmetic8
+            | DeterministicStage::InterestingValues8 => 1,
+            DeterministicStage::BitFlip2
+            | DeterministicStage::ByteFlip2
+            | DeterministicStage::Arithmetic16
+            | DeterministicStage::InterestingValues16 => 2,
+            DeterministicStage::BitFlip4
+            | DeterministicStage::ByteFlip4
+            | DeterministicStage::Arithmetic32
+            | DeterministicStage::InterestingValues32 => 4,
+        }
+    }
+
+    // Multi-byte values are tried in both big and little endian
+    fn endians(self) -> usize {
+        if self.width() > 1 {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn positions(self, len: usize) -> usize {
+        let units = match self {
+            DeterministicStage::BitFlip1
+            | DeterministicStage::BitFlip2
+            | DeterministicStage::BitFlip4 => len * 8,
+            _ => len,
+        };
+        (units + 1).saturating_sub(self.width())
+    }
+
+    fn steps(self) -> usize {
+        match self {
+            DeterministicStage::BitFlip1
+            | DeterministicStage::BitFlip2
+            | DeterministicStage::BitFlip4
+            | DeterministicStage::ByteFlip1
+            | DeterministicStage::ByteFlip2
+            | DeterministicStage::ByteFlip4 => 1,
+            DeterministicStage::Arithmetic8
+            | DeterministicStage::Arithmetic16
+            | DeterministicStage::Arithmetic32 => 2 * ARITH_MAX * self.endians(),
+            DeterministicStage::InterestingValues8 => MAGIC_8.len(),
+            DeterministicStage::InterestingValues16 => MAGIC_16.len() * 2,
+            DeterministicStage::InterestingValues32 => MAGIC_32.len() * 2,
+        }
+    }
+}
+
+fn read_uint(bytes: &[u8], big_endian: bool) -> u32 {
+    let fold = |acc: u32, &b: &u8| acc << 8 | b as u32;
+    if big_endian {
+        bytes.iter().fold(0, fold)
+    } else {
+        bytes.iter().rev().fold(0, fold)
+    }
+}
+
+fn write_uint(bytes: &mut [u8], val: u32, big_endian: bool) {
+    let width = bytes.len();
+    for (i, b) in bytes.iter_mut().enumerate() {
+        let shift = if big_endian { width - i - 1 } else { i };
+        *b = (val >> (8 * shift)) as u8;
+    }
+}
+
+/// Lazily yields one mutated copy of `base` per position/value of a deterministic stage.
+#[derive(Debug, Clone)]
+pub struct DeterministicIter {
+    base: Vec<u8>,
+    stage: DeterministicStage,
+    pos: usize,
+    step: usize,
+}
+
+impl DeterministicIter {
+    pub fn new(base: Vec<u8>, stage: DeterministicStage) -> Self {
+        DeterministicIter {
+            base,
+            stage,
+            pos: 0,
+            step: 0,
+        }
+    }
+
+    fn apply(&self, out: &mut [u8]) {
+        let width = self.stage.width();
+        match self.stage {
+            DeterministicStage::BitFlip1
+            | DeterministicStage::BitFlip2
+            | DeterministicStage::BitFlip4 => {
+                for bit in self.pos..self.pos + width {
+                    out[bit >> 3] ^= 0x80 >> (bit & 7);
+                }
+            }
+            DeterministicStage::ByteFlip1
+            | DeterministicStage::ByteFlip2
+            | DeterministicStage::ByteFlip4 => {
+                out[self.pos..self.pos + width]
+                    .iter_mut()
+                    .for_each(|b| *b ^= 0xff);
+            }
+            DeterministicStage::Arithmetic8
+            | DeterministicStage::Arithmetic16
+            | DeterministicStage::Arithmetic32 => {
+                let big_endian = self.step < 2 * ARITH_MAX;
+                let rem = self.step % (2 * ARITH_MAX);
+                let delta = (rem / 2 + 1) as u32;
+                let field = &mut out[self.pos..self.pos + width];
+                let val = read_uint(field, big_endian);
+                let val = if rem.is_multiple_of(2) {
+                    val.wrapping_add(delta)
+                } else {
+                    val.wrapping_sub(delta)
+                };
+                write_uint(field, val, big_endian);
+            }
+            DeterministicStage::InterestingValues8 => {
+                out[self.pos] = MAGIC_8[self.step];
+            }
+            DeterministicStage::InterestingValues16 => {
+                let big_endian = self.step < MAGIC_16.len();
+                let val = MAGIC_16[self.step % MAGIC_16.len()] as u32;
+                write_uint(&mut out[self.pos..self.pos + width], val, big_endian);
+            }
+            DeterministicStage::InterestingValues32 => {
+                let big_endian = self.step < MAGIC_32.len();
+                let val = MAGIC_32[self.step % MAGIC_32.len()];
+                write_uint(&mut out[self.pos..self.pos + width], val, big_endian);
+            }
+        }
+    }
+}
+
+impl Iterator for DeterministicIter {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        if self.pos >= self.stage.positions(self.base.len()) {
+            return None;
+        }
+        let mut out = self.base.clone();
+        self.apply(&mut out);
+        self.step += 1;
+        if self.step == self.stage.steps() {
+            self.step = 0;
+            self.pos += 1;
+        }
+        Some(out)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let total = self.stage.positions(self.base.len()) * self.stage.steps();
+        let done = self.pos * self.stage.steps() + self.step;
+        let remaining = total.saturating_sub(done);
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_flip_stages_walk_every_bit() {
+        let outputs: Vec<Vec<u8>> =
+            DeterministicIter::new(vec![0, 0], DeterministicStage::BitFlip1).collect();
+        assert_eq!(outputs.len(), 16);
+        assert_eq!(outputs[0], [0x80, 0]);
+        assert_eq!(outputs[15], [0, 0x01]);
+
+        let outputs: Vec<Vec<u8>> =
+            DeterministicIter::new(vec![0, 0], DeterministicStage::BitFlip4).collect();
+        assert_eq!(outputs.len(), 13);
+        assert_eq!(outputs[6], [0x03, 0xc0]);
+    }
+
+    #[test]
+    fn byte_flip_and_arithmetic_stages() {
+        let outputs: Vec<Vec<u8>> =
+            DeterministicIter::new(vec![0, 0, 0], DeterministicStage::ByteFlip2).collect();
+        assert_eq!(outputs, vec![vec![0xff, 0xff, 0], vec![0, 0xff, 0xff]]);
+
+        let mut iter = DeterministicIter::new(vec![0x10], DeterministicStage::Arithmetic8);
+        assert_eq!(iter.size_hint(), (2 * ARITH_MAX, Some(2 * ARITH_MAX)));
+        assert_eq!(iter.next(), Some(vec![0x11]));
+        assert_eq!(iter.next(), Some(vec![0x0f]));
+        assert_eq!(
+            iter.last(),
+            Some(vec![0x10u8.wrapping_sub(ARITH_MAX as u8)])
+        );
+
+        let outputs: Vec<Vec<u8>> =
+            DeterministicIter::new(vec![0x00, 0xff], DeterministicStage::Arithmetic16).collect();
+        assert_eq!(outputs.len(), 4 * ARITH_MAX);
+        assert_eq!(outputs[0], [0x01, 0x00]);
+        assert_eq!(outputs[2 * ARITH_MAX], [0x01, 0xff]);
+    }
+
+    #[test]
+    fn interesting_value_stages_cover_tables() {
+        let outputs: Vec<Vec<u8>> =
+            DeterministicIter::new(vec![0; 4], DeterministicStage::InterestingValues32).collect();
+        assert_eq!(outputs.len(), MAGIC_32.len() * 2);
+        assert_eq!(outputs[0], MAGIC_32[0].to_be_bytes());
+        assert_eq!(outputs[MAGIC_32.len()], MAGIC_32[0].to_le_bytes());
+
+        assert_eq!(
+            DeterministicIter::new(vec![0; 1], DeterministicStage::InterestingValues16).count(),
+            0
+        );
+        for stage in DeterministicStage::ALL {
+            assert_eq!(DeterministicIter::new(Vec::new(), stage).count(), 0);
+        }
+    }
+}
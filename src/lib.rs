@@ -1,37 +1,395 @@
+pub mod corpus;
+pub mod deterministic;
 pub mod magic;
 
+pub use corpus::{Corpus, CorpusStats};
+pub use deterministic::{DeterministicIter, DeterministicStage};
+
 use core::clone::Clone;
 use core::cmp::PartialOrd;
-use log::debug;
+use log::{debug, warn};
 use magic::{MAGIC_16, MAGIC_32, MAGIC_64, MAGIC_8};
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 const BYTE_POS: [u8; 8] = [1, 2, 4, 8, 16, 32, 64, 128];
 const BYTE_RANGE: [u8; 3] = [2, 4, 8];
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TestCase {
     pub data: Vec<u8>,
     pub size: usize,
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    InvalidBounds { min_size: usize, max_size: usize },
+    TooSmall { size: usize, min_size: usize },
+    TooLarge { size: usize, max_size: usize },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::InvalidBounds { min_size, max_size } => {
+                write!(f, "min size {min_size} exceeds max size {max_size}")
+            }
+            BuildError::TooSmall { size, min_size } => {
+                write!(f, "test case of {size} bytes is below min size {min_size}")
+            }
+            BuildError::TooLarge { size, max_size } => {
+                write!(f, "test case of {size} bytes exceeds max size {max_size}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+#[derive(Debug, Default)]
+pub struct TestCaseBuilder {
+    data: Vec<u8>,
+    max_size: Option<usize>,
+    min_size: Option<usize>,
+    metadata: HashMap<String, String>,
+}
+
+impl TestCaseBuilder {
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = Some(min_size);
+        self
+    }
+
+    pub fn metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn build(self) -> Result<TestCase, BuildError> {
+        let size = self.data.len();
+        if let (Some(min_size), Some(max_size)) = (self.min_size, self.max_size) {
+            if min_size > max_size {
+                return Err(BuildError::InvalidBounds { min_size, max_size });
+            }
+        }
+        if let Some(min_size) = self.min_size.filter(|&m| size < m) {
+            return Err(BuildError::TooSmall { size, min_size });
+        }
+        if let Some(max_size) = self.max_size.filter(|&m| size > m) {
+            return Err(BuildError::TooLarge { size, max_size });
+        }
+        Ok(TestCase {
+            data: self.data,
+            size,
+            metadata: self.metadata,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    OddLength(usize),
+    InvalidHexChar { index: usize, ch: char },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::OddLength(len) => write!(f, "hex string has odd length {len}"),
+            ParseError::InvalidHexChar { index, ch } => {
+                write!(f, "invalid hex character {ch:?} at index {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MutationError {
+    EmptyCorpus,
+    EmptyDictionary,
+    InvalidRange { min: usize, max: usize },
+    TestCaseTooSmall { size: usize, required: usize },
+    CorpusIndexOutOfRange { index: usize, len: usize },
+    NoMutators,
+    DuplicateMutator(Mutator),
+    MutationExhausted { limit: u64 },
+    ZeroRngState { step: usize },
+}
+
+impl fmt::Display for MutationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MutationError::EmptyCorpus => write!(f, "corpus is empty"),
+            MutationError::EmptyDictionary => write!(f, "dictionary is empty"),
+            MutationError::InvalidRange { min, max } => {
+                write!(f, "invalid range: min {min} exceeds max {max}")
+            }
+            MutationError::TestCaseTooSmall { size, required } => {
+                write!(f, "test case of {size} bytes is too small, need {required}")
+            }
+            MutationError::CorpusIndexOutOfRange { index, len } => {
+                write!(f, "corpus index {index} out of range for {len} entries")
+            }
+            MutationError::NoMutators => write!(f, "mutator list is empty"),
+            MutationError::DuplicateMutator(m) => write!(f, "mutator {m} is listed twice"),
+            MutationError::MutationExhausted { limit } => {
+                write!(f, "mutation limit of {limit} reached")
+            }
+            MutationError::ZeroRngState { step } => {
+                write!(f, "rng state of step {step} is zero")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MutationError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverwriteError {
+    pub range: Range<usize>,
+    pub size: usize,
 }
 
+impl fmt::Display for OverwriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot overwrite bytes {}..{} of a {} byte test case",
+            self.range.start, self.range.end, self.size
+        )
+    }
+}
+
+impl std::error::Error for OverwriteError {}
+
 impl Default for TestCase {
     fn default() -> Self {
         TestCase {
             data: Vec::with_capacity(4096),
             size: 4096,
+            metadata: HashMap::new(),
         }
     }
 }
 
+// TryFrom<&str> comes for free through the blanket impl
+impl From<&str> for TestCase {
+    fn from(s: &str) -> Self {
+        TestCase::new(s.as_bytes())
+    }
+}
+
+impl io::Write for TestCase {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.extend_from_slice(buf);
+        self.size = self.data.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads a test case's data from the start, created by `TestCase::reader`.
+#[derive(Debug, Clone)]
+pub struct TestCaseReader<'a> {
+    test_case: &'a TestCase,
+    read_pos: usize,
+}
+
+impl io::Read for TestCaseReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = &self.test_case.data[self.read_pos..];
+        let n = buf.len().min(remaining.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.read_pos += n;
+        Ok(n)
+    }
+}
+
+impl std::str::FromStr for TestCase {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(TestCase::from(s))
+    }
+}
+
 impl TestCase {
-    pub fn new(data: &Vec<u8>) -> Self {
+    pub fn new(data: &[u8]) -> Self {
         TestCase {
-            data: data.clone(),
+            data: data.to_vec(),
             size: data.len(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Starts a `TestCaseBuilder`, e.g.
+    /// `TestCase::with_initial_data(bytes).max_size(8192).metadata("source", "corpus/foo.bin").build()`.
+    pub fn with_initial_data(data: Vec<u8>) -> TestCaseBuilder {
+        TestCaseBuilder {
+            data,
+            ..Default::default()
+        }
+    }
+
+    /// Decodes a string of hex digit pairs such as `"deadbeef0102"`.
+    pub fn from_hex_string(s: &str) -> Result<TestCase, ParseError> {
+        if !s.len().is_multiple_of(2) {
+            return Err(ParseError::OddLength(s.len()));
+        }
+        let nibble = |index: usize, c: u8| {
+            (c as char)
+                .to_digit(16)
+                .map(|d| d as u8)
+                .ok_or_else(|| ParseError::InvalidHexChar {
+                    index,
+                    ch: s[index..].chars().next().unwrap_or('?'),
+                })
+        };
+        let bytes = s.as_bytes();
+        let mut data = Vec::with_capacity(bytes.len() / 2);
+        for i in (0..bytes.len()).step_by(2) {
+            data.push(nibble(i, bytes[i])? << 4 | nibble(i + 1, bytes[i + 1])?);
+        }
+        Ok(TestCase::new(&data))
+    }
+
+    /// Formats the data as a lowercase hex string without separators.
+    pub fn to_hex_string(&self) -> String {
+        let mut out = String::with_capacity(self.data.len() * 2);
+        for b in &self.data {
+            let _ = write!(out, "{b:02x}");
+        }
+        out
+    }
+
+    /// Number of differing bits between `a` and `b` over the length of the shorter one.
+    pub fn hamming_distance(a: &TestCase, b: &TestCase) -> usize {
+        a.data
+            .iter()
+            .zip(&b.data)
+            .map(|(x, y)| (x ^ y).count_ones() as usize)
+            .sum()
+    }
+
+    #[inline]
+    pub fn count_byte(&self, b: u8) -> usize {
+        self.data.iter().filter(|&&x| x == b).count()
+    }
+
+    #[inline]
+    pub fn count_zeros(&self) -> usize {
+        self.count_byte(0)
+    }
+
+    #[inline]
+    pub fn count_nonzeros(&self) -> usize {
+        self.data.len() - self.count_byte(0)
+    }
+
+    /// 64-bit FNV-1a hash of the data, a cheap non-cryptographic key for deduplication.
+    pub fn fingerprint(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+        self.data.iter().fold(FNV_OFFSET_BASIS, |hash, &b| {
+            (hash ^ b as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
+
+    #[inline]
+    pub fn positions_of(&self, b: u8) -> Vec<usize> {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, &x)| x == b)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Starting offsets of every (possibly overlapping) occurrence of `needle`.
+    pub fn windows_containing(&self, needle: &[u8]) -> Vec<usize> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        self.data
+            .windows(needle.len())
+            .enumerate()
+            .filter(|(_, w)| *w == needle)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn find_first(&self, needle: &[u8]) -> Option<usize> {
+        if needle.is_empty() {
+            return None;
+        }
+        self.data.windows(needle.len()).position(|w| w == needle)
+    }
+
+    pub fn find_last(&self, needle: &[u8]) -> Option<usize> {
+        if needle.is_empty() {
+            return None;
+        }
+        self.data.windows(needle.len()).rposition(|w| w == needle)
+    }
+
+    /// Replaces `data.len()` bytes starting at `offset`, failing if they do not fit.
+    pub fn overwrite_at(&mut self, offset: usize, data: &[u8]) -> Result<(), OverwriteError> {
+        let end = offset.saturating_add(data.len());
+        if end > self.data.len() {
+            return Err(OverwriteError {
+                range: offset..end,
+                size: self.data.len(),
+            });
+        }
+        self.data[offset..end].copy_from_slice(data);
+        Ok(())
+    }
+
+    /// `io::Read` adapter over the data, for parsers that take `impl Read`.
+    pub fn reader(&self) -> TestCaseReader<'_> {
+        TestCaseReader {
+            test_case: self,
+            read_pos: 0,
+        }
+    }
+
+    /// XORs the buffer with `key` repeated over its length. Applying the same key twice
+    /// restores the original data; an empty key leaves it unchanged.
+    pub fn apply_xor_key(&mut self, key: &[u8]) {
+        if key.is_empty() {
+            return;
+        }
+        for (b, k) in self.data.iter_mut().zip(key.iter().cycle()) {
+            *b ^= k;
         }
     }
+
+    /// Grows the buffer with `fill` bytes or truncates it, keeping `size` in sync.
+    pub fn resize(&mut self, new_size: usize, fill: u8) {
+        self.data.resize(new_size, fill);
+        self.size = new_size;
+    }
 }
 
 #[cfg(target_arch = "x86_64")]
@@ -50,18 +408,35 @@ fn get_rdtsc() -> usize {
 }
 
 #[derive(Debug, Default)]
-pub struct Rng(usize);
+pub struct Rng(usize, usize);
+
+// Nonzero stand-in for a zero state, which xorshift would never leave
+const NONZERO_STATE: usize = 0x5fd89eda3130256d;
 
 impl Rng {
     pub fn new(seed: usize) -> Self {
-        if seed == 0 {
-            Rng(0x5fd89eda3130256d ^ get_rdtsc())
+        let seed = if seed == 0 {
+            NONZERO_STATE ^ get_rdtsc()
         } else {
-            Rng(seed)
-        }
+            seed
+        };
+        Rng(seed, seed)
+    }
+
+    /// Current state. `Rng::new(prng.seed())` continues the sequence from this point.
+    #[inline]
+    pub fn seed(&self) -> usize {
+        self.0
+    }
+
+    /// Seed this generator started from, with the time-based seed filled in for `0`.
+    #[inline]
+    pub fn initial_seed(&self) -> usize {
+        self.1
     }
 
     #[inline]
+    #[must_use]
     pub fn rand(&mut self) -> usize {
         let value = self.0;
         self.0 ^= self.0 << 13;
@@ -71,30 +446,114 @@ impl Rng {
     }
 
     #[inline]
+    #[must_use]
     pub fn gen_range(&mut self, min: usize, max: usize) -> usize {
         assert!(max >= min, "Failed bounds check");
         if min == max {
             return min;
         }
-        if min == 0 && max == core::usize::MAX {
+        if min == 0 && max == usize::MAX {
             return self.rand();
         }
 
-        min + (self.rand() % (max - min + 1))
+        let span = max - min + 1;
+        if span > u32::MAX as usize {
+            return min + self.gen_usize_mod_unbiased(span);
+        }
+        min + (self.rand() % span)
+    }
+
+    /// Like `gen_range`, but returns an error instead of panicking when `min > max`.
+    #[inline]
+    pub fn try_gen_range(&mut self, min: usize, max: usize) -> Result<usize, MutationError> {
+        if max < min {
+            return Err(MutationError::InvalidRange { min, max });
+        }
+        Ok(self.gen_range(min, max))
+    }
+
+    /// Uniform value in `[0, modulus)` without modulo bias, using rejection sampling.
+    #[inline]
+    #[must_use]
+    pub fn gen_usize_mod_unbiased(&mut self, modulus: usize) -> usize {
+        assert!(modulus > 0, "Modulus must be non-zero");
+        let threshold = usize::MAX - (usize::MAX % modulus);
+        loop {
+            let val = self.rand();
+            if val < threshold {
+                return val % modulus;
+            }
+        }
+    }
+
+    /// Index into `weights` picked with probability proportional to its weight. Falls back
+    /// to a uniform pick when every weight is zero. Panics if `weights` is empty or, on
+    /// targets where `usize` is narrower than 64 bits, if the weights sum past `usize::MAX`.
+    #[must_use]
+    pub fn gen_weighted_index(&mut self, weights: &[u32]) -> usize {
+        assert!(!weights.is_empty(), "Weights must not be empty");
+        let prefix: Vec<u64> = weights
+            .iter()
+            .scan(0u64, |acc, &w| {
+                *acc += w as u64;
+                Some(*acc)
+            })
+            .collect();
+        let total = prefix[prefix.len() - 1];
+        if total == 0 {
+            return self.rand() % weights.len();
+        }
+        let total = usize::try_from(total).expect("Sum of weights must fit in usize");
+        let target = self.gen_usize_mod_unbiased(total) as u64;
+        prefix.partition_point(|&p| p <= target)
+    }
+
+    /// `k` distinct indices in `[0, len)` in random order, using a partial Fisher-Yates
+    /// shuffle over a sparse swap map so it runs in O(k).
+    #[must_use]
+    pub fn sample_slice_indices(&mut self, len: usize, k: usize) -> Vec<usize> {
+        assert!(k <= len, "Cannot sample more indices than the slice holds");
+        let mut swapped: HashMap<usize, usize> = HashMap::with_capacity(k);
+        let mut out = Vec::with_capacity(k);
+        for i in 0..k {
+            let j = self.gen_range(i, len - 1);
+            let at_i = *swapped.get(&i).unwrap_or(&i);
+            let at_j = *swapped.get(&j).unwrap_or(&j);
+            swapped.insert(j, at_i);
+            out.push(at_j);
+        }
+        out
+    }
+
+    /// Uniform float in `[0.0, 1.0)` built from the top 53 bits of the next value.
+    #[inline]
+    #[must_use]
+    pub fn gen_float(&mut self) -> f64 {
+        (self.rand() as u64 >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Returns `true` with probability `probability_true`, clamped to `[0.0, 1.0]`.
+    #[inline]
+    #[must_use]
+    pub fn gen_bool_weighted(&mut self, probability_true: f64) -> bool {
+        self.gen_float() < probability_true.clamp(0.0, 1.0)
     }
 
     #[inline]
+    #[must_use]
     pub fn gen_byte(&mut self) -> u8 {
-        (self.rand() % 255) as u8
+        self.rand() as u8
     }
 
     #[inline]
+    #[must_use]
     pub fn choose<T: PartialOrd + Clone>(&mut self, entries: &[T]) -> T {
         let idx = self.rand() % entries.len();
         entries[idx].clone()
     }
 
     #[inline]
+    #[must_use]
     pub fn bool(&mut self) -> bool {
         self.choose(&[true, false])
     }
@@ -102,16 +561,67 @@ impl Rng {
     #[inline]
     pub fn fill_bytes(&mut self, buf: &mut Vec<u8>, sz: usize) {
         while buf.len() < sz {
-            buf.extend_from_slice(&self.rand().to_ne_bytes());
+            let bytes = self.rand().to_ne_bytes();
+            let n = (sz - buf.len()).min(bytes.len());
+            buf.extend_from_slice(&bytes[..n]);
         }
     }
 }
 
-#[derive(Debug, Clone)]
+impl From<Rng> for u64 {
+    fn from(prng: Rng) -> Self {
+        prng.0 as u64
+    }
+}
+
+/// Restores a PRNG from its saved state. On 32-bit targets the upper half of `state` is
+/// truncated, so only states saved on the same target round-trip exactly. A zero state is
+/// replaced by a fixed nonzero one.
+impl From<u64> for Rng {
+    fn from(state: u64) -> Self {
+        let state = match state as usize {
+            0 => NONZERO_STATE,
+            s => s,
+        };
+        Rng(state, state)
+    }
+}
+
+// Walks 1-byte tag, 1-byte length records and clamps every length that runs past the
+// end of the buffer, so the records parse again after bytes were removed or inserted
+fn fix_tlv_lengths(data: &mut [u8]) {
+    let mut pos = 0;
+    while pos + 2 <= data.len() {
+        let remaining = data.len() - pos - 2;
+        let len = (data[pos + 1] as usize)
+            .min(remaining)
+            .min(u8::MAX as usize);
+        data[pos + 1] = len as u8;
+        pos += 2 + len;
+    }
+}
+
+fn byte_runs(data: &[u8]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for i in 1..=data.len() {
+        if i == data.len() || data[i] != data[start] {
+            if i - start >= 2 {
+                runs.push((start, i - start));
+            }
+            start = i;
+        }
+    }
+    runs
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Mutator {
     BitFlip,
     ByteFlip,
     NegateByte,
+    IncrementByte,
+    DecrementByte,
     SwapNeighbors,
     SwapEndianness,
     Arithmetic,
@@ -123,8 +633,266 @@ pub enum Mutator {
     Truncate,
     Append,
     Set,
+    InsertMagicSequence,
+    PairwiseFlip,
+    ReplaceByte,
+    NullTerminate,
+    ExpandRunLength,
+    ContractRunLength,
+    GradientByteFlip,
+    SignedOverflow,
+    SwapWords,
+    ReverseBytes,
+    ReverseRange,
+    ChopAndJoin,
+    RandomSplice,
+    IncreaseMutation,
+    RandomWalk,
+    HighEntropyInsert,
+    CorruptMagicBytes,
+    FieldBoundaryMutation,
+    InsertLineTerminator,
+    RemoveLineTerminator,
+    PadToAlignment,
+    BitFlipRange,
+    UpperLowerToggle,
+    InsertCRLF,
+    NormalizeLineEndings,
+    FullRandomize,
+    CopyAndModify,
+    EndOfInput,
     Splice,
+    InterleaveCorpus,
+    TruncateAndAppend,
+    FloodWithPattern,
     InsertFromDict,
+    SwapDictTokens,
+    ProtocolKeyword,
+    DeleteToken,
+}
+
+impl Mutator {
+    /// Inverting a range is the same operation as `BitFlipRange`: every byte in a
+    /// contiguous block is XORed with 0xff. `BitFlip` instead toggles scattered bits.
+    #[allow(non_upper_case_globals)]
+    pub const InvertRange: Mutator = Mutator::BitFlipRange;
+}
+
+impl fmt::Display for Mutator {
+    // Kebab-case name derived from the variant, e.g. `BitFlip` -> `bit-flip`. Acronyms stay
+    // in one word, so `InsertCRLF` -> `insert-crlf`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name: Vec<char> = format!("{self:?}").chars().collect();
+        for (i, &ch) in name.iter().enumerate() {
+            if ch.is_ascii_uppercase() && i > 0 {
+                let prev_upper = name[i - 1].is_ascii_uppercase();
+                let next_lower = name.get(i + 1).is_some_and(|c| c.is_ascii_lowercase());
+                if !prev_upper || next_lower {
+                    f.write_char('-')?;
+                }
+            }
+            f.write_char(ch.to_ascii_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+/// Input format hint that biases mutator selection, see `set_target_format`. For `Tlv`
+/// the length fields are also repaired after each mutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetFormat {
+    Binary,
+    Ascii,
+    Json,
+    Http,
+    Tlv,
+}
+
+// Favored mutators are picked this many times as often as the rest
+const FAVORED_WEIGHT: u32 = 4;
+
+impl TargetFormat {
+    fn favors(self, mutator: &Mutator) -> bool {
+        match self {
+            TargetFormat::Binary => matches!(
+                mutator,
+                Mutator::Arithmetic | Mutator::InsertConstants | Mutator::SwapEndianness
+            ),
+            TargetFormat::Ascii => matches!(
+                mutator,
+                Mutator::InsertLineTerminator
+                    | Mutator::ProtocolKeyword
+                    | Mutator::UpperLowerToggle
+            ),
+            TargetFormat::Json => matches!(
+                mutator,
+                Mutator::InsertFromDict | Mutator::SwapDictTokens | Mutator::ProtocolKeyword
+            ),
+            TargetFormat::Http => matches!(
+                mutator,
+                Mutator::InsertLineTerminator
+                    | Mutator::RemoveLineTerminator
+                    | Mutator::ProtocolKeyword
+                    | Mutator::SwapDictTokens
+                    | Mutator::InsertCRLF
+                    | Mutator::NormalizeLineEndings
+            ),
+            // Length fields are repaired after every mutation, see `fix_tlv_lengths`
+            TargetFormat::Tlv => matches!(
+                mutator,
+                Mutator::Arithmetic
+                    | Mutator::SignedOverflow
+                    | Mutator::SwapEndianness
+                    | Mutator::FieldBoundaryMutation
+            ),
+        }
+    }
+}
+
+// Mutators that only need a test case and are enabled by default
+const BASE_MUTATORS: [Mutator; 44] = [
+    Mutator::BitFlip,
+    Mutator::ByteFlip,
+    Mutator::NegateByte,
+    Mutator::IncrementByte,
+    Mutator::DecrementByte,
+    Mutator::SwapNeighbors,
+    Mutator::SwapEndianness,
+    Mutator::Arithmetic,
+    Mutator::DeleteBytes,
+    Mutator::DeleteRange,
+    Mutator::CopyRange,
+    Mutator::CopyBytes,
+    Mutator::InsertConstants,
+    Mutator::Truncate,
+    Mutator::Append,
+    Mutator::Set,
+    Mutator::InsertMagicSequence,
+    Mutator::PairwiseFlip,
+    Mutator::ReplaceByte,
+    Mutator::NullTerminate,
+    Mutator::ExpandRunLength,
+    Mutator::ContractRunLength,
+    Mutator::GradientByteFlip,
+    Mutator::SignedOverflow,
+    Mutator::SwapWords,
+    Mutator::ReverseBytes,
+    Mutator::ReverseRange,
+    Mutator::ChopAndJoin,
+    Mutator::RandomSplice,
+    Mutator::IncreaseMutation,
+    Mutator::RandomWalk,
+    Mutator::HighEntropyInsert,
+    Mutator::CorruptMagicBytes,
+    Mutator::FieldBoundaryMutation,
+    Mutator::InsertLineTerminator,
+    Mutator::RemoveLineTerminator,
+    Mutator::PadToAlignment,
+    Mutator::BitFlipRange,
+    Mutator::UpperLowerToggle,
+    Mutator::InsertCRLF,
+    Mutator::NormalizeLineEndings,
+    Mutator::FullRandomize,
+    Mutator::CopyAndModify,
+    Mutator::EndOfInput,
+];
+
+// Mutators that need a corpus and are only enabled while one is set
+const CORPUS_MUTATORS: [Mutator; 4] = [
+    Mutator::Splice,
+    Mutator::InterleaveCorpus,
+    Mutator::TruncateAndAppend,
+    Mutator::FloodWithPattern,
+];
+
+// Mutators that need a dictionary and are only enabled while one is set
+const DICT_MUTATORS: [Mutator; 4] = [
+    Mutator::InsertFromDict,
+    Mutator::SwapDictTokens,
+    Mutator::ProtocolKeyword,
+    Mutator::DeleteToken,
+];
+
+#[derive(Debug, Clone)]
+pub struct MutationInfo {
+    pub mutator: Mutator,
+    pub byte_offset: Option<usize>,
+    pub mutation_length: usize,
+    pub size_before: usize,
+    pub size_after: usize,
+}
+
+impl fmt::Display for MutationInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mutator)?;
+        if let Some(offset) = self.byte_offset {
+            write!(f, " at offset {offset}")?;
+        }
+        write!(
+            f,
+            " ({} bytes), size {} -> {}",
+            self.mutation_length, self.size_before, self.size_after
+        )
+    }
+}
+
+// Fixed seed so calibration runs are comparable between engines
+const CALIBRATION_SEED: usize = 0x5eed_ca11;
+
+/// Per-mutator results of `run_calibration`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MutatorCalibration {
+    pub mutator: Mutator,
+    pub applied: usize,
+    pub avg_output_size: f64,
+    pub panicked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationReport {
+    pub iterations: usize,
+    pub mutators: Vec<MutatorCalibration>,
+}
+
+impl CalibrationReport {
+    /// Mutators that panicked. All of them are disabled, except when one is the last
+    /// mutator left.
+    pub fn disabled(&self) -> Vec<Mutator> {
+        self.mutators
+            .iter()
+            .filter(|c| c.panicked)
+            .map(|c| c.mutator.clone())
+            .collect()
+    }
+}
+
+/// Input before and after a mutation, as returned by `mutate_and_diff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MutationDiff {
+    pub original: Vec<u8>,
+    pub mutated: Vec<u8>,
+    /// Indices whose byte differs, including every index past the end of the shorter buffer.
+    pub changed_bytes: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EngineSnapshot {
+    pub prng_state: usize,
+    pub data: Vec<u8>,
+    pub size: usize,
+}
+
+/// Campaign state saved by `MutationEngine::checkpoint_and_reset`.
+#[derive(Debug, Clone)]
+pub struct EngineCheckpoint {
+    pub prng_state: usize,
+    pub test_case: TestCase,
+    pub total_mutations: u64,
+    pub mutation_multiplier: f64,
+    pub mutator: Mutator,
+    pub mutation_offset: Option<usize>,
+    pub mutation_length: usize,
+    pub last_info: Option<MutationInfo>,
 }
 
 #[derive(Debug)]
@@ -132,9 +900,25 @@ pub struct MutationEngine {
     pub mutator: Mutator,
     pub test_case: TestCase,
     pub prng: Rng,
-    pub mutators: Vec<Mutator>,
-    pub token_dict: Option<Vec<String>>,
+    mutators: Vec<Mutator>,
+    pub token_dict: Option<Vec<Vec<u8>>>,
     pub corpus: Option<Arc<Vec<Vec<u8>>>>,
+    format_magic: Option<Vec<u8>>,
+    byte_positions: Vec<u8>,
+    byte_ranges: Vec<u8>,
+    alignment: usize,
+    eof_marker: Vec<u8>,
+    target_format: Option<TargetFormat>,
+    pinned: bool,
+    last_info: Option<MutationInfo>,
+    mutation_offset: Option<usize>,
+    mutation_length: usize,
+    mutation_multiplier: f64,
+    total_mutations: u64,
+    mutation_limit: Option<u64>,
+    // State right after construction, restored by `checkpoint_and_reset`
+    initial_prng_state: usize,
+    initial_test_case: TestCase,
 }
 
 impl MutationEngine {
@@ -144,28 +928,14 @@ impl MutationEngine {
         token_dict: Option<Vec<String>>,
         corpus: Option<Arc<Vec<Vec<u8>>>>,
     ) -> Self {
-        let mut mutators = [
-            Mutator::BitFlip,
-            Mutator::ByteFlip,
-            Mutator::NegateByte,
-            Mutator::SwapNeighbors,
-            Mutator::SwapEndianness,
-            Mutator::Arithmetic,
-            Mutator::DeleteBytes,
-            Mutator::DeleteRange,
-            Mutator::CopyRange,
-            Mutator::CopyBytes,
-            Mutator::InsertConstants,
-            Mutator::Truncate,
-            Mutator::Append,
-            Mutator::Set,
-        ]
-        .to_vec();
+        let mut mutators = BASE_MUTATORS.to_vec();
+        let token_dict: Option<Vec<Vec<u8>>> =
+            token_dict.map(|d| d.into_iter().map(String::into_bytes).collect());
         if token_dict.is_some() {
-            mutators.push(Mutator::InsertFromDict);
+            mutators.extend_from_slice(&DICT_MUTATORS);
         }
         if corpus.is_some() {
-            mutators.push(Mutator::Splice);
+            mutators.extend_from_slice(&CORPUS_MUTATORS);
         }
         let mut prng = if let Some(seed) = prng_seed {
             Rng::new(seed)
@@ -180,6 +950,8 @@ impl MutationEngine {
             prng.fill_bytes(&mut tc.data, tc.size);
             tc
         };
+        let initial_prng_state = prng.0;
+        let initial_test_case = test_case.clone();
         MutationEngine {
             mutator: Mutator::BitFlip,
             test_case,
@@ -187,86 +959,887 @@ impl MutationEngine {
             mutators,
             token_dict,
             corpus,
+            format_magic: None,
+            byte_positions: BYTE_POS.to_vec(),
+            byte_ranges: BYTE_RANGE.to_vec(),
+            alignment: 4,
+            eof_marker: vec![0x00],
+            target_format: None,
+            pinned: false,
+            last_info: None,
+            mutation_offset: None,
+            mutation_length: 0,
+            mutation_multiplier: 1.0,
+            total_mutations: 0,
+            mutation_limit: None,
+            initial_prng_state,
+            initial_test_case,
         }
     }
 
-    #[inline]
-    fn mutation_size(&mut self) -> usize {
-        let mutation_factor = ((self.prng.gen_range(0, 10) + 1) as f64) * 0.01;
-        (self.test_case.size as f64 * mutation_factor) as usize + 1
+    /// Starts a `MutationEngineBuilder`, e.g.
+    /// `MutationEngine::new_with_builder().seed(0x1337).corpus(corpus).build()`.
+    pub fn new_with_builder() -> MutationEngineBuilder {
+        MutationEngineBuilder::default()
     }
 
-    fn get_mutator(&mut self, num: usize) {
-        self.mutator = match num {
-            0 => Mutator::BitFlip,
-            1 => Mutator::ByteFlip,
-            2 => Mutator::NegateByte,
-            3 => Mutator::SwapNeighbors,
-            4 => Mutator::SwapEndianness,
-            5 => Mutator::Arithmetic,
-            6 => Mutator::DeleteBytes,
-            7 => Mutator::DeleteRange,
-            8 => Mutator::CopyBytes,
-            9 => Mutator::CopyRange,
-            10 => Mutator::InsertConstants,
-            11 => Mutator::Truncate,
-            12 => Mutator::Append,
-            13 => Mutator::Set,
-            14 => Mutator::Splice,
-            15 => Mutator::InsertFromDict,
-            _ => unreachable!(),
+    /// Derives a seed from `rdtsc`, the current thread, the wall clock and a stack
+    /// address (ASLR). Preferred over relying on `rdtsc` alone.
+    pub fn seed_from_entropy() -> usize {
+        let mut hasher = DefaultHasher::new();
+        std::thread::current().id().hash(&mut hasher);
+        let thread = hasher.finish() as usize;
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as usize);
+        let marker = 0u8;
+        let stack = &marker as *const u8 as usize;
+        let seed = get_rdtsc() ^ thread ^ time.rotate_left(17) ^ stack.rotate_left(41);
+        if seed == 0 {
+            NONZERO_STATE
+        } else {
+            seed
         }
     }
 
-    fn select_random_test_case(&mut self) {
-        self.test_case.data.clear();
-        if let Some(corp) = &self.corpus {
-            assert!(corp.len() > 0, "Corpus does not contain any files.");
-            let chosen = &corp[self.prng.rand() % corp.len()];
-            self.test_case.data.extend_from_slice(chosen);
-            self.test_case.size = chosen.len();
-        } else {
-            let sz: usize = 4096;
-            let mut data = Vec::with_capacity(sz);
-            self.prng.fill_bytes(&mut data, sz);
-            self.test_case = TestCase::new(&data);
-        }
+    /// File-format magic that `InsertMagicSequence` restores at offset 0, if any.
+    pub fn format_magic(&self) -> Option<&[u8]> {
+        self.format_magic.as_deref()
     }
 
-    pub fn mutate(&mut self) -> &Vec<u8> {
-        let m = self.prng.gen_range(0, self.mutators.len() - 1);
-        self.get_mutator(m);
-        debug!("Chosen Mutator: {:#?}", self.mutator);
-        self.select_random_test_case();
-        match self.mutator {
-            Mutator::BitFlip => self.bit_flip(),
-            Mutator::ByteFlip => self.byte_flip(),
-            Mutator::NegateByte => self.negate_byte(),
-            Mutator::SwapNeighbors => self.swap_neighbors(),
-            Mutator::SwapEndianness => self.swap_with_width(),
-            Mutator::Arithmetic => self.arithmetic(),
-            Mutator::DeleteBytes => self.delete_single_bytes(),
-            Mutator::DeleteRange => self.delete_byte_range(),
-            Mutator::CopyBytes => self.copy_single_bytes(),
-            Mutator::CopyRange => self.copy_byte_range(),
-            Mutator::InsertConstants => self.insert_constants(),
-            Mutator::Truncate => self.truncate(),
-            Mutator::Append => self.append(),
-            Mutator::Set => self.set(),
-            Mutator::Splice => self.splice(),
-            Mutator::InsertFromDict => self.insert_from_dict(),
+    /// Sets the file-format magic that `InsertMagicSequence` restores at offset 0.
+    pub fn set_format_magic(&mut self, magic: Vec<u8>) {
+        self.format_magic = if magic.is_empty() { None } else { Some(magic) };
+    }
+
+    /// Bit masks `BitFlip` chooses from.
+    pub fn byte_positions(&self) -> &[u8] {
+        &self.byte_positions
+    }
+
+    /// Field widths used by `Arithmetic` and `SwapEndianness`.
+    pub fn byte_ranges(&self) -> &[u8] {
+        &self.byte_ranges
+    }
+
+    /// Sets the bit masks `BitFlip` chooses from (defaults to `BYTE_POS`).
+    pub fn set_byte_positions(&mut self, positions: Vec<u8>) {
+        assert!(!positions.is_empty(), "Byte positions must not be empty.");
+        self.byte_positions = positions;
+    }
+
+    /// Sets the field widths used by `Arithmetic` and `SwapEndianness` (defaults to `BYTE_RANGE`).
+    pub fn set_byte_ranges(&mut self, ranges: Vec<u8>) {
+        assert!(!ranges.is_empty(), "Byte ranges must not be empty.");
+        assert!(
+            ranges.iter().all(|r| (1..=16).contains(r)),
+            "Byte ranges must be between 1 and 16 bytes."
+        );
+        self.byte_ranges = ranges;
+    }
+
+    /// Boundary `PadToAlignment` pads the buffer length to.
+    #[inline]
+    pub fn alignment(&self) -> usize {
+        self.alignment
+    }
+
+    /// Sets the boundary `PadToAlignment` pads the buffer length to (defaults to 4).
+    pub fn set_alignment(&mut self, alignment: usize) {
+        assert!(alignment > 0, "Alignment must be non-zero.");
+        self.alignment = alignment;
+    }
+
+    /// Marker `EndOfInput` appends or inserts.
+    pub fn eof_marker(&self) -> &[u8] {
+        &self.eof_marker
+    }
+
+    /// Sets the marker `EndOfInput` appends or inserts, e.g. `\x1a` or a record
+    /// terminator (defaults to `\x00`).
+    pub fn set_eof_marker(&mut self, marker: Vec<u8>) {
+        assert!(!marker.is_empty(), "EOF marker must not be empty.");
+        self.eof_marker = marker;
+    }
+
+    /// Mutators `mutate()` currently picks from.
+    pub fn effective_mutators(&self) -> &[Mutator] {
+        &self.mutators
+    }
+
+    /// Replaces the enabled mutators. The list must be non-empty and free of duplicates,
+    /// and corpus or dictionary mutators need a corpus or dictionary to be set.
+    pub fn set_mutators(&mut self, mutators: Vec<Mutator>) -> Result<(), MutationError> {
+        if mutators.is_empty() {
+            return Err(MutationError::NoMutators);
+        }
+        for (i, m) in mutators.iter().enumerate() {
+            if mutators[..i].contains(m) {
+                return Err(MutationError::DuplicateMutator(m.clone()));
+            }
+            if CORPUS_MUTATORS.contains(m) && self.corpus_len() == 0 {
+                return Err(MutationError::EmptyCorpus);
+            }
+            if DICT_MUTATORS.contains(m) && self.dict_len() == 0 {
+                return Err(MutationError::EmptyDictionary);
+            }
+        }
+        self.mutators = mutators;
+        Ok(())
+    }
+
+    /// Biases `mutate()` towards the mutators that suit `fmt`. Without a target format every
+    /// enabled mutator is equally likely.
+    pub fn set_target_format(&mut self, fmt: TargetFormat) {
+        self.target_format = Some(fmt);
+    }
+
+    /// Removes the target format, making every enabled mutator equally likely again.
+    pub fn clear_target_format(&mut self) {
+        self.target_format = None;
+    }
+
+    pub fn target_format(&self) -> Option<TargetFormat> {
+        self.target_format
+    }
+
+    /// Number of corpus entries, or 0 if no corpus is set.
+    #[inline]
+    pub fn corpus_len(&self) -> usize {
+        self.corpus.as_ref().map_or(0, |c| c.len())
+    }
+
+    /// Replaces the corpus at runtime and enables the corpus-based mutators. An empty
+    /// corpus is rejected and leaves the engine unchanged.
+    pub fn set_corpus(&mut self, corpus: Arc<Vec<Vec<u8>>>) -> Result<(), MutationError> {
+        if corpus.is_empty() {
+            return Err(MutationError::EmptyCorpus);
+        }
+        self.corpus = Some(corpus);
+        for m in CORPUS_MUTATORS {
+            if !self.mutators.contains(&m) {
+                self.mutators.push(m);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds every file in a libFuzzer corpus directory that is not already in the corpus.
+    /// File names are ignored. Returns the number of newly added entries.
+    pub fn import_libfuzzer_corpus(&mut self, dir: &Path) -> Result<usize, io::Error> {
+        let mut corpus = Corpus::new(self.corpus.as_deref().cloned().unwrap_or_default());
+        let added = corpus.merge_from_dir(dir)?;
+        if added > 0 {
+            self.set_corpus(corpus.into())
+                .expect("corpus has at least one new entry");
+        }
+        Ok(added)
+    }
+
+    /// Writes every corpus entry to `dir` in AFL queue format (`id:000000,src:hantu`, ...).
+    /// Returns the number of files written.
+    pub fn export_corpus(&self, dir: &Path) -> Result<usize, io::Error> {
+        let entries = match &self.corpus {
+            Some(corpus) => corpus.as_slice(),
+            None => return Ok(0),
+        };
+        std::fs::create_dir_all(dir)?;
+        for (id, entry) in entries.iter().enumerate() {
+            std::fs::write(dir.join(format!("id:{id:06},src:hantu")), entry)?;
+        }
+        Ok(entries.len())
+    }
+
+    /// Removes the corpus and disables the corpus-based mutators. If no mutator is left, the
+    /// default set is enabled again.
+    pub fn clear_corpus(&mut self) {
+        self.corpus = None;
+        self.mutators.retain(|m| !CORPUS_MUTATORS.contains(m));
+        self.reset_mutators_if_empty();
+    }
+
+    /// Replaces the token dictionary at runtime and enables the dictionary-based mutators.
+    /// An empty dictionary is rejected and leaves the engine unchanged.
+    pub fn set_dictionary(&mut self, dict: Vec<Vec<u8>>) -> Result<(), MutationError> {
+        if dict.is_empty() {
+            return Err(MutationError::EmptyDictionary);
+        }
+        self.token_dict = Some(dict);
+        for m in DICT_MUTATORS {
+            if !self.mutators.contains(&m) {
+                self.mutators.push(m);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the token dictionary and disables the dictionary-based mutators. If no
+    /// mutator is left, the default set is enabled again.
+    pub fn clear_dictionary(&mut self) {
+        self.token_dict = None;
+        self.mutators.retain(|m| !DICT_MUTATORS.contains(m));
+        self.reset_mutators_if_empty();
+    }
+
+    // Falls back to the mutators `new` would enable for the current corpus and dictionary
+    fn reset_mutators_if_empty(&mut self) {
+        if !self.mutators.is_empty() {
+            return;
+        }
+        self.mutators = BASE_MUTATORS.to_vec();
+        if self.token_dict.is_some() {
+            self.mutators.extend_from_slice(&DICT_MUTATORS);
+        }
+        if self.corpus.is_some() {
+            self.mutators.extend_from_slice(&CORPUS_MUTATORS);
+        }
+    }
+
+    /// Sum of all corpus entry sizes in bytes, or 0 if no corpus is set.
+    pub fn corpus_bytes_total(&self) -> usize {
+        self.corpus
+            .as_ref()
+            .map_or(0, |c| c.iter().map(|e| e.len()).sum())
+    }
+
+    /// Number of dictionary tokens, or 0 if no dictionary is set.
+    #[inline]
+    pub fn dict_len(&self) -> usize {
+        self.token_dict.as_ref().map_or(0, |d| d.len())
+    }
+
+    /// Returns the dictionary token at `idx`, if any.
+    #[inline]
+    pub fn dict_token(&self, idx: usize) -> Option<&[u8]> {
+        self.token_dict.as_ref()?.get(idx).map(|t| t.as_slice())
+    }
+
+    /// Captures the PRNG state and current test case so they can be rolled back later.
+    pub fn snapshot(&self) -> EngineSnapshot {
+        EngineSnapshot {
+            prng_state: self.prng.0,
+            data: self.test_case.data.clone(),
+            size: self.test_case.size,
+        }
+    }
+
+    /// Rolls the PRNG state and current test case back to `snap`.
+    pub fn restore(&mut self, snap: EngineSnapshot) {
+        self.prng.0 = snap.prng_state;
+        self.test_case.data = snap.data;
+        self.test_case.size = snap.size;
+    }
+
+    /// Saves the PRNG state, current test case and mutation statistics, then resets them to
+    /// how the engine was constructed. Corpus, dictionary and settings are kept.
+    pub fn checkpoint_and_reset(&mut self) -> EngineCheckpoint {
+        let checkpoint = EngineCheckpoint {
+            prng_state: self.prng.0,
+            test_case: std::mem::replace(&mut self.test_case, self.initial_test_case.clone()),
+            total_mutations: self.total_mutations,
+            mutation_multiplier: self.mutation_multiplier,
+            mutator: self.mutator.clone(),
+            mutation_offset: self.mutation_offset,
+            mutation_length: self.mutation_length,
+            last_info: self.last_info.take(),
+        };
+        self.prng.0 = self.initial_prng_state;
+        self.total_mutations = 0;
+        self.mutation_multiplier = 1.0;
+        self.mutator = Mutator::BitFlip;
+        self.record_mutation(None, 0);
+        checkpoint
+    }
+
+    /// Resumes the campaign saved in `cp`.
+    pub fn restore_checkpoint(&mut self, cp: EngineCheckpoint) {
+        self.prng.0 = cp.prng_state;
+        self.test_case = cp.test_case;
+        self.total_mutations = cp.total_mutations;
+        self.mutation_multiplier = cp.mutation_multiplier;
+        self.mutator = cp.mutator;
+        self.mutation_offset = cp.mutation_offset;
+        self.mutation_length = cp.mutation_length;
+        self.last_info = cp.last_info;
+    }
+
+    /// Lazily walks every position/value of an AFL-style deterministic `stage` over the
+    /// current test case, leaving the engine untouched.
+    pub fn iter_deterministic(&self, stage: DeterministicStage) -> impl Iterator<Item = Vec<u8>> {
+        DeterministicIter::new(self.test_case.data.clone(), stage)
+    }
+
+    /// Metadata about the most recent `mutate()` call, if any.
+    pub fn last_mutation_info(&self) -> Option<&MutationInfo> {
+        self.last_info.as_ref()
+    }
+
+    /// Human-readable sentence describing the most recent mutation, e.g.
+    /// `"bit-flip: flipped 3 bytes at offset 42 (size 100 -> 100)"`.
+    pub fn explain_last_mutation(&self) -> String {
+        let info = match &self.last_info {
+            Some(info) => info,
+            None => return "no mutation applied yet".to_string(),
+        };
+        let verb = match info.mutator {
+            // Falls back to overwriting like `InsertFromDict` on binary input
+            Mutator::ProtocolKeyword if info.size_after == info.size_before => "overwrote",
+            Mutator::NormalizeLineEndings if info.size_after < info.size_before => "deleted",
+            Mutator::NormalizeLineEndings => "inserted",
+            Mutator::InsertMagicSequence
+                if info.size_after == info.size_before + info.mutation_length =>
+            {
+                "inserted"
+            }
+            Mutator::BitFlip
+            | Mutator::ByteFlip
+            | Mutator::GradientByteFlip
+            | Mutator::PairwiseFlip
+            | Mutator::NegateByte
+            | Mutator::BitFlipRange => "flipped",
+            Mutator::IncrementByte
+            | Mutator::DecrementByte
+            | Mutator::Arithmetic
+            | Mutator::SignedOverflow
+            | Mutator::RandomWalk => "adjusted",
+            Mutator::SwapNeighbors | Mutator::SwapEndianness | Mutator::SwapWords => "swapped",
+            Mutator::DeleteBytes
+            | Mutator::DeleteRange
+            | Mutator::Truncate
+            | Mutator::ContractRunLength
+            | Mutator::ChopAndJoin
+            | Mutator::RemoveLineTerminator
+            | Mutator::DeleteToken => "deleted",
+            Mutator::CopyBytes
+            | Mutator::CopyRange
+            | Mutator::Append
+            | Mutator::ExpandRunLength
+            | Mutator::HighEntropyInsert
+            | Mutator::InsertLineTerminator
+            | Mutator::PadToAlignment
+            | Mutator::ProtocolKeyword
+            | Mutator::InsertCRLF
+            | Mutator::CopyAndModify
+            | Mutator::EndOfInput => "inserted",
+            Mutator::ReverseBytes | Mutator::ReverseRange => "reversed",
+            Mutator::RandomSplice => "copied",
+            Mutator::Splice | Mutator::InterleaveCorpus | Mutator::TruncateAndAppend => {
+                "spliced in"
+            }
+            _ => "overwrote",
+        };
+        let mut out = format!("{}: {} {} bytes", info.mutator, verb, info.mutation_length);
+        if let Some(offset) = info.byte_offset {
+            let _ = write!(out, " at offset {offset}");
+        }
+        let _ = write!(out, " (size {} -> {})", info.size_before, info.size_after);
+        out
+    }
+
+    #[inline(always)]
+    fn mutation_size(&mut self) -> usize {
+        let mutation_factor = ((self.prng.gen_range(0, 10) + 1) as f64) * 0.01;
+        self.mutation_length =
+            (self.test_case.size as f64 * mutation_factor * self.mutation_multiplier) as usize + 1;
+        self.mutation_length
+    }
+
+    #[inline]
+    fn record_mutation(&mut self, offset: Option<usize>, len: usize) {
+        self.mutation_offset = offset;
+        self.mutation_length = len;
+    }
+
+    fn get_mutator(&mut self, num: usize) {
+        self.mutator = self.mutators[num].clone();
+    }
+
+    // Shortest input `mutator` can run on without indexing out of bounds
+    fn min_size(&self, mutator: &Mutator) -> usize {
+        match mutator {
+            Mutator::Arithmetic | Mutator::SwapEndianness => {
+                self.byte_ranges.iter().copied().max().unwrap_or(0) as usize
+            }
+            Mutator::InsertConstants => std::mem::size_of::<u64>(),
+            Mutator::SwapNeighbors => 2,
+            Mutator::BitFlip
+            | Mutator::ByteFlip
+            | Mutator::GradientByteFlip
+            | Mutator::NegateByte
+            | Mutator::IncrementByte
+            | Mutator::DecrementByte
+            | Mutator::Set
+            | Mutator::DeleteBytes
+            | Mutator::DeleteRange
+            | Mutator::CopyBytes
+            | Mutator::CopyRange
+            | Mutator::Append
+            | Mutator::RandomWalk
+            | Mutator::UpperLowerToggle
+            | Mutator::Splice => 1,
+            _ => 0,
+        }
+    }
+
+    #[inline]
+    fn fits(&self, mutator: &Mutator) -> bool {
+        self.test_case.data.len() >= self.min_size(mutator)
+    }
+
+    /// Keeps `mutate()` working on the current test case instead of picking a new one, so
+    /// successive mutations stack on the same input.
+    pub fn pin_test_case(&mut self) {
+        self.pinned = true;
+    }
+
+    /// Lets `mutate()` pick a fresh test case again.
+    pub fn unpin_test_case(&mut self) {
+        self.pinned = false;
+    }
+
+    #[inline]
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn select_random_test_case(&mut self) {
+        if self.pinned {
+            return;
+        }
+        self.test_case.data.clear();
+        if let Some(corp) = &self.corpus {
+            assert!(!corp.is_empty(), "Corpus does not contain any files.");
+            let chosen = &corp[self.prng.rand() % corp.len()];
+            self.test_case.data.extend_from_slice(chosen);
+            self.test_case.size = chosen.len();
+        } else {
+            let sz: usize = 4096;
+            let mut data = Vec::with_capacity(sz);
+            self.prng.fill_bytes(&mut data, sz);
+            self.test_case = TestCase::new(&data);
+        }
+    }
+
+    /// Caps the engine at `n` total mutations. Past the limit `mutate()` panics and
+    /// `try_mutate()` returns `MutationError::MutationExhausted`.
+    pub fn set_mutation_count_limit(&mut self, n: u64) {
+        self.mutation_limit = Some(n);
+    }
+
+    fn check_mutation_limit(&self) -> Result<(), MutationError> {
+        match self.mutation_limit {
+            Some(limit) if self.total_mutations >= limit => {
+                Err(MutationError::MutationExhausted { limit })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Shared body of the mutate variants. When `original` is given, the selected input is
+    // copied into it before the mutator runs.
+    fn mutate_once(&mut self, original: Option<&mut Vec<u8>>) -> Result<(), MutationError> {
+        self.check_mutation_limit()?;
+        self.prepare_mutation()?;
+        if let Some(original) = original {
+            original.clone_from(&self.test_case.data);
+        }
+        self.apply_mutator();
+        self.total_mutations += 1;
+        Ok(())
+    }
+
+    // Like `mutate`, but returns `false` instead of panicking once the mutation limit is hit
+    fn mutate_within_limit(&mut self) -> bool {
+        match self.mutate_once(None) {
+            Ok(()) => true,
+            Err(MutationError::MutationExhausted { .. }) => false,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    #[must_use = "call mutate() and pass the result to the fuzzing harness"]
+    pub fn mutate(&mut self) -> &Vec<u8> {
+        if let Err(e) = self.mutate_once(None) {
+            panic!("{e}");
         }
         &self.test_case.data
     }
 
+    /// Applies `n` randomly chosen mutators to the current test case with a fixed seed and
+    /// reports how each one behaved. Mutators that need a longer input are skipped, and
+    /// mutators that panic are disabled unless that would leave none enabled. Apart from
+    /// that the engine state is left unchanged.
+    ///
+    /// Panics are caught with `catch_unwind`, so under `panic = "abort"` (as in this crate's
+    /// release profile) a panicking mutator aborts the process instead.
+    pub fn run_calibration(&mut self, n: usize) -> CalibrationReport {
+        let initial = self.test_case.clone();
+        let mutator = self.mutator.clone();
+        let last_info = self.last_info.take();
+        let mut stats: Vec<MutatorCalibration> = self
+            .mutators
+            .iter()
+            .map(|m| MutatorCalibration {
+                mutator: m.clone(),
+                applied: 0,
+                avg_output_size: 0.0,
+                panicked: false,
+            })
+            .collect();
+
+        let prng = std::mem::replace(&mut self.prng, Rng::new(CALIBRATION_SEED));
+        for _ in 0..n {
+            let idx = self.prng.gen_range(0, self.mutators.len() - 1);
+            self.mutator = self.mutators[idx].clone();
+            self.test_case = initial.clone();
+            if !self.fits(&self.mutator) {
+                continue;
+            }
+            let result = panic::catch_unwind(AssertUnwindSafe(|| self.apply_mutator()));
+            self.mutation_multiplier = 1.0;
+            let entry = &mut stats[idx];
+            entry.applied += 1;
+            if result.is_err() {
+                entry.panicked = true;
+            } else {
+                entry.avg_output_size += self.test_case.data.len() as f64;
+            }
+        }
+        for entry in stats.iter_mut() {
+            if entry.applied > 0 {
+                entry.avg_output_size /= entry.applied as f64;
+            }
+        }
+
+        let report = CalibrationReport {
+            iterations: n,
+            mutators: stats,
+        };
+        for m in report.disabled() {
+            if self.mutators.len() == 1 {
+                warn!("Keeping mutator {m} despite a panic, it is the last one enabled");
+                break;
+            }
+            warn!("Disabling mutator {m} after it panicked during calibration");
+            self.mutators.retain(|x| *x != m);
+        }
+        self.test_case = initial;
+        self.prng = prng;
+        self.mutator = mutator;
+        self.last_info = last_info;
+        report
+    }
+
+    /// Like `mutate`, but also returns the selected input before mutation and the indices
+    /// of the bytes that changed.
+    pub fn mutate_and_diff(&mut self) -> MutationDiff {
+        let mut original = Vec::new();
+        if let Err(e) = self.mutate_once(Some(&mut original)) {
+            panic!("{e}");
+        }
+        let mutated = self.test_case.data.clone();
+        let common = original.len().min(mutated.len());
+        let mut changed_bytes: Vec<usize> = original
+            .iter()
+            .zip(mutated.iter())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, _)| i)
+            .collect();
+        changed_bytes.extend(common..original.len().max(mutated.len()));
+        MutationDiff {
+            original,
+            mutated,
+            changed_bytes,
+        }
+    }
+
+    fn prepare_mutation(&mut self) -> Result<(), MutationError> {
+        if self.mutators.is_empty() {
+            return Err(MutationError::NoMutators);
+        }
+        let m = match self.target_format {
+            Some(fmt) => {
+                let weights: Vec<u32> = self
+                    .mutators
+                    .iter()
+                    .map(|m| if fmt.favors(m) { FAVORED_WEIGHT } else { 1 })
+                    .collect();
+                self.prng.gen_weighted_index(&weights)
+            }
+            None => self.prng.gen_range(0, self.mutators.len() - 1),
+        };
+        self.get_mutator(m);
+        self.select_random_test_case();
+        if !self.fits(&self.mutator) {
+            // Re-pick among the mutators that can handle an input this short
+            let fitting: Vec<usize> = (0..self.mutators.len())
+                .filter(|&i| self.fits(&self.mutators[i]))
+                .collect();
+            if fitting.is_empty() {
+                return Err(MutationError::TestCaseTooSmall {
+                    size: self.test_case.data.len(),
+                    required: self
+                        .mutators
+                        .iter()
+                        .map(|m| self.min_size(m))
+                        .min()
+                        .unwrap_or(0),
+                });
+            }
+            let pick = fitting[self.prng.gen_range(0, fitting.len() - 1)];
+            self.get_mutator(pick);
+        }
+        debug!("Chosen Mutator: {:#?}", self.mutator);
+        Ok(())
+    }
+
+    /// Like `mutate`, but rejects an empty corpus, empty corpus entries and an empty
+    /// dictionary up front and returns an error instead of panicking. Mutators that
+    /// need a longer input than the selected one are skipped; if none of the enabled
+    /// mutators fits, `TestCaseTooSmall` is returned.
+    pub fn try_mutate(&mut self) -> Result<&Vec<u8>, MutationError> {
+        self.check_mutation_limit()?;
+        if let Some(corpus) = &self.corpus {
+            if corpus.is_empty() {
+                return Err(MutationError::EmptyCorpus);
+            }
+            // Splice draws from any entry, so only empty ones are a problem
+            if corpus.iter().any(|e| e.is_empty()) {
+                return Err(MutationError::TestCaseTooSmall {
+                    size: 0,
+                    required: 1,
+                });
+            }
+        }
+        if let Some(dict) = &self.token_dict {
+            if dict.is_empty() {
+                return Err(MutationError::EmptyDictionary);
+            }
+        }
+        self.mutate_once(None)?;
+        Ok(&self.test_case.data)
+    }
+
+    /// Keeps the first `split_idx` bytes of the current test case and replaces the rest with
+    /// corpus entry `corpus_idx` from the same offset on.
+    pub fn mutate_splice_at(
+        &mut self,
+        split_idx: usize,
+        corpus_idx: usize,
+    ) -> Result<&Vec<u8>, MutationError> {
+        let corpus = match &self.corpus {
+            Some(corpus) if !corpus.is_empty() => corpus,
+            _ => return Err(MutationError::EmptyCorpus),
+        };
+        if corpus_idx >= corpus.len() {
+            return Err(MutationError::CorpusIndexOutOfRange {
+                index: corpus_idx,
+                len: corpus.len(),
+            });
+        }
+        if split_idx > self.test_case.data.len() {
+            return Err(MutationError::TestCaseTooSmall {
+                size: self.test_case.data.len(),
+                required: split_idx,
+            });
+        }
+        self.check_mutation_limit()?;
+        let splice_idx = split_idx.min(corpus[corpus_idx].len());
+        let size_before = self.test_case.data.len();
+        self.mutator = Mutator::Splice;
+        self.splice_at(split_idx, corpus_idx, splice_idx);
+        self.finish_mutation(size_before);
+        self.total_mutations += 1;
+        Ok(&self.test_case.data)
+    }
+
+    /// Applies every enabled mutator once to the current test case and returns the outputs
+    /// in `mutators` order. A mutator that needs a longer input is not applied and its slot
+    /// holds the unchanged input. The current test case is left unchanged.
+    pub fn apply_all_mutators_once(&mut self) -> Vec<Vec<u8>> {
+        let initial = self.test_case.clone();
+        let mut outputs = Vec::with_capacity(self.mutators.len());
+        for mutator in self.mutators.clone() {
+            self.test_case = initial.clone();
+            self.mutator = mutator;
+            if self.fits(&self.mutator) {
+                self.apply_mutator();
+            }
+            outputs.push(self.test_case.data.clone());
+        }
+        self.test_case = initial;
+        outputs
+    }
+
+    /// Replays `(mutator, rng_state)` pairs on the current test case, reseeding the PRNG
+    /// before each step. Returns the data produced by the final step.
+    ///
+    /// Fails on a zero state, which xorshift never leaves, and on a step whose mutator
+    /// needs a longer input than the previous steps left behind.
+    pub fn apply_mutation_sequence(
+        &mut self,
+        seq: &[(Mutator, u64)],
+    ) -> Result<Vec<u8>, MutationError> {
+        if let Some(step) = seq.iter().position(|(_, state)| *state as usize == 0) {
+            return Err(MutationError::ZeroRngState { step });
+        }
+        for (mutator, rng_state) in seq {
+            if !self.fits(mutator) {
+                return Err(MutationError::TestCaseTooSmall {
+                    size: self.test_case.data.len(),
+                    required: self.min_size(mutator),
+                });
+            }
+            self.prng.0 = *rng_state as usize;
+            self.mutator = mutator.clone();
+            self.apply_mutator();
+        }
+        Ok(self.test_case.data.clone())
+    }
+
+    fn apply_mutator(&mut self) {
+        let size_before = self.test_case.data.len();
+        self.record_mutation(None, 0);
+        match self.mutator {
+            Mutator::BitFlip => self.bit_flip(),
+            Mutator::ByteFlip => self.byte_flip(),
+            Mutator::NegateByte => self.negate_byte(),
+            Mutator::IncrementByte => self.increment_byte(),
+            Mutator::DecrementByte => self.decrement_byte(),
+            Mutator::SwapNeighbors => self.swap_neighbors(),
+            Mutator::SwapEndianness => self.swap_with_width(),
+            Mutator::Arithmetic => self.arithmetic(),
+            Mutator::DeleteBytes => self.delete_single_bytes(),
+            Mutator::DeleteRange => self.delete_byte_range(),
+            Mutator::CopyBytes => self.copy_single_bytes(),
+            Mutator::CopyRange => self.copy_byte_range(),
+            Mutator::InsertConstants => self.insert_constants(),
+            Mutator::Truncate => self.truncate(),
+            Mutator::Append => self.append(),
+            Mutator::Set => self.set(),
+            Mutator::InsertMagicSequence => self.insert_magic_sequence(),
+            Mutator::PairwiseFlip => self.pairwise_flip(),
+            Mutator::ReplaceByte => self.replace_byte(),
+            Mutator::NullTerminate => self.null_terminate(),
+            Mutator::ExpandRunLength => self.expand_run_length(),
+            Mutator::ContractRunLength => self.contract_run_length(),
+            Mutator::GradientByteFlip => self.gradient_byte_flip(),
+            Mutator::SignedOverflow => self.signed_overflow(),
+            Mutator::SwapWords => self.swap_words(),
+            Mutator::ReverseBytes => self.reverse_bytes(),
+            Mutator::ReverseRange => self.reverse_range(),
+            Mutator::ChopAndJoin => self.chop_and_join(),
+            Mutator::RandomSplice => self.random_splice(),
+            Mutator::IncreaseMutation => self.increase_mutation(),
+            Mutator::RandomWalk => self.random_walk(),
+            Mutator::HighEntropyInsert => self.high_entropy_insert(),
+            Mutator::CorruptMagicBytes => self.corrupt_magic_bytes(),
+            Mutator::FieldBoundaryMutation => self.field_boundary_mutation(),
+            Mutator::InsertLineTerminator => self.insert_line_terminator(),
+            Mutator::RemoveLineTerminator => self.remove_line_terminator(),
+            Mutator::PadToAlignment => self.pad_to_alignment(),
+            Mutator::BitFlipRange => self.bit_flip_range(),
+            Mutator::UpperLowerToggle => self.upper_lower_toggle(),
+            Mutator::InsertCRLF => self.insert_crlf(),
+            Mutator::NormalizeLineEndings => self.normalize_line_endings(),
+            Mutator::FullRandomize => self.full_randomize(),
+            Mutator::CopyAndModify => self.copy_and_modify(),
+            Mutator::EndOfInput => self.end_of_input(),
+            Mutator::Splice => self.splice(),
+            Mutator::InsertFromDict => self.insert_from_dict(),
+            Mutator::InterleaveCorpus => self.interleave_corpus(),
+            Mutator::TruncateAndAppend => self.truncate_and_append(),
+            Mutator::FloodWithPattern => self.flood_with_pattern(),
+            Mutator::SwapDictTokens => self.swap_dict_tokens(),
+            Mutator::ProtocolKeyword => self.protocol_keyword(),
+            Mutator::DeleteToken => self.delete_token(),
+        }
+        self.finish_mutation(size_before);
+    }
+
+    // Repairs the target format after a mutation and records its metadata
+    fn finish_mutation(&mut self, size_before: usize) {
+        if self.target_format == Some(TargetFormat::Tlv) {
+            fix_tlv_lengths(&mut self.test_case.data);
+        }
+        self.update_last_info(size_before);
+    }
+
+    fn update_last_info(&mut self, size_before: usize) {
+        self.last_info = Some(MutationInfo {
+            mutator: self.mutator.clone(),
+            byte_offset: self.mutation_offset,
+            mutation_length: self.mutation_length,
+            size_before,
+            size_after: self.test_case.data.len(),
+        });
+    }
+
+    /// Feeds mutations to `harness` until it reports no new coverage for `window`
+    /// consecutive inputs or the mutation count limit is reached. Returns the number of
+    /// mutations performed.
+    pub fn fuzz_until_coverage_stable<F>(&mut self, mut harness: F, window: usize) -> usize
+    where
+        F: FnMut(&[u8]) -> usize,
+    {
+        let mut execs = 0;
+        let mut stale = 0;
+        while stale < window && self.mutate_within_limit() {
+            let new_coverage = harness(&self.test_case.data);
+            execs += 1;
+            if new_coverage == 0 {
+                stale += 1;
+            } else {
+                stale = 0;
+            }
+        }
+        execs
+    }
+
+    /// Feeds mutations to `harness` until `duration` has elapsed or the mutation count limit
+    /// is reached. Returns the number of mutations performed and the inputs that made the
+    /// harness panic.
+    ///
+    /// Harness panics are caught with `catch_unwind`, so under `panic = "abort"` (as in this
+    /// crate's release profile) the first crash aborts the process instead of being recorded.
+    pub fn fuzz_with_timeout<F>(
+        &mut self,
+        mut harness: F,
+        duration: Duration,
+    ) -> (usize, Vec<Vec<u8>>)
+    where
+        F: FnMut(&[u8]),
+    {
+        let deadline = Instant::now() + duration;
+        let mut execs = 0;
+        let mut crashes = Vec::new();
+        while Instant::now() < deadline && self.mutate_within_limit() {
+            let input = &self.test_case.data;
+            execs += 1;
+            if panic::catch_unwind(AssertUnwindSafe(|| harness(input))).is_err() {
+                crashes.push(input.clone());
+            }
+        }
+        (execs, crashes)
+    }
+
     fn bit_flip(&mut self) {
         for _ in 0..self.mutation_size() {
             let rng_idx = self.prng.gen_range(0, self.test_case.size - 1);
-            let rng_byte_pos = self.prng.choose(&BYTE_POS);
+            let rng_byte_pos = self.prng.choose(&self.byte_positions);
             self.test_case.data[rng_idx] ^= rng_byte_pos;
         }
     }
 
+    fn gradient_byte_flip(&mut self) {
+        for _ in 0..self.mutation_size() {
+            let rng_idx = self.prng.gen_range(0, self.test_case.size - 1);
+            let mask = if self.prng.bool() { 0x80 } else { 0x01 };
+            self.test_case.data[rng_idx] ^= mask;
+        }
+    }
+
     fn byte_flip(&mut self) {
         for _ in 0..self.mutation_size() {
             let rng_idx = self.prng.gen_range(0, self.test_case.size - 1);
@@ -281,6 +1854,7 @@ impl MutationEngine {
         self.test_case.data[rng_idx..rng_idx + len]
             .iter_mut()
             .for_each(|x| *x = to_set);
+        self.record_mutation(Some(rng_idx), len);
     }
 
     fn negate_byte(&mut self) {
@@ -290,6 +1864,20 @@ impl MutationEngine {
         }
     }
 
+    fn increment_byte(&mut self) {
+        for _ in 0..self.mutation_size() {
+            let rng_idx = self.prng.gen_range(0, self.test_case.size - 1);
+            self.test_case.data[rng_idx] = self.test_case.data[rng_idx].wrapping_add(1);
+        }
+    }
+
+    fn decrement_byte(&mut self) {
+        for _ in 0..self.mutation_size() {
+            let rng_idx = self.prng.gen_range(0, self.test_case.size - 1);
+            self.test_case.data[rng_idx] = self.test_case.data[rng_idx].wrapping_sub(1);
+        }
+    }
+
     fn swap_neighbors(&mut self) {
         for _ in 0..self.mutation_size() {
             let rng_idx = self.prng.gen_range(0, self.test_case.size - 2);
@@ -297,9 +1885,24 @@ impl MutationEngine {
         }
     }
 
+    fn swap_words(&mut self) {
+        let mut width = self.prng.choose(&[4, 8]);
+        if self.test_case.size < 2 * width {
+            width = 4;
+        }
+        if self.test_case.size < 2 * width {
+            return;
+        }
+        let a = self.prng.gen_range(0, self.test_case.size - 2 * width);
+        let b = self.prng.gen_range(a + width, self.test_case.size - width);
+        let (head, tail) = self.test_case.data.split_at_mut(b);
+        head[a..a + width].swap_with_slice(&mut tail[..width]);
+        self.record_mutation(Some(a), width);
+    }
+
     fn swap_with_width(&mut self) {
         for _ in 0..self.mutation_size() {
-            let rng_byte_range = self.prng.choose(&BYTE_RANGE) as usize;
+            let rng_byte_range = self.prng.choose(&self.byte_ranges) as usize;
             let rng_idx = self.prng.gen_range(0, self.test_case.size - rng_byte_range);
             for i in 0..(rng_byte_range >> 1) {
                 let tmp = self.test_case.data[rng_idx + i];
@@ -310,9 +1913,10 @@ impl MutationEngine {
         }
     }
 
+    #[inline(never)]
     fn arithmetic(&mut self) {
         for _ in 0..self.mutation_size() {
-            let rng_byte_range = self.prng.choose(&BYTE_RANGE) as usize;
+            let rng_byte_range = self.prng.choose(&self.byte_ranges) as usize;
             let rng_idx = self.prng.gen_range(0, self.test_case.size - rng_byte_range);
             // TODO measure if it has an impact when making this a bool that flips
             // after each call to have alternate adds/subs
@@ -367,17 +1971,42 @@ impl MutationEngine {
                             ((val >> (8 * (val_sz - (i + 1)))) & 0xff) as u8;
                     }
                 }
-                _ => {
-                    unreachable!()
+                width => {
+                    let mut val: u128 = self.test_case.data[rng_idx..rng_idx + width]
+                        .iter()
+                        .fold(0, |acc, &b| acc << 8 | b as u128);
+                    if op {
+                        val = val.wrapping_add(1);
+                    } else {
+                        val = val.wrapping_sub(1);
+                    }
+                    for i in 0..width {
+                        self.test_case.data[rng_idx + i] =
+                            ((val >> (8 * (width - (i + 1)))) & 0xff) as u8;
+                    }
                 }
             };
         }
     }
 
-    fn delete_single_bytes(&mut self) {
+    fn signed_overflow(&mut self) {
         for _ in 0..self.mutation_size() {
-            let idx = self.prng.gen_range(0, self.test_case.size - 1);
-            self.test_case.data.remove(idx);
+            let rng_byte_range = self.prng.choose(&self.byte_ranges) as usize;
+            if self.test_case.size < rng_byte_range {
+                continue;
+            }
+            let slots = self.test_case.size / rng_byte_range;
+            let rng_idx = self.prng.gen_range(0, slots - 1) * rng_byte_range;
+            // Big-endian two's complement minimum: sign bit set, everything else clear
+            self.test_case.data[rng_idx] = 0x80;
+            self.test_case.data[rng_idx + 1..rng_idx + rng_byte_range].fill(0);
+        }
+    }
+
+    fn delete_single_bytes(&mut self) {
+        for _ in 0..self.mutation_size() {
+            let idx = self.prng.gen_range(0, self.test_case.size - 1);
+            self.test_case.data.remove(idx);
             self.test_case.size -= 1;
         }
     }
@@ -385,7 +2014,9 @@ impl MutationEngine {
     fn delete_byte_range(&mut self) {
         let m_sz = self.mutation_size();
         let idx = self.prng.gen_range(0, self.test_case.size - m_sz);
-        let _drained: Vec<_> = self.test_case.data.drain(idx..idx + m_sz).collect();
+        self.test_case.data.drain(idx..idx + m_sz);
+        self.test_case.size -= m_sz;
+        self.record_mutation(Some(idx), m_sz);
     }
 
     fn copy_single_bytes(&mut self) {
@@ -403,9 +2034,29 @@ impl MutationEngine {
         for i in 0..m_sz {
             self.test_case.data[to + i] = self.test_case.data[from + i];
         }
+        self.record_mutation(Some(to), m_sz);
     }
 
+    fn copy_and_modify(&mut self) {
+        let len = self.test_case.data.len();
+        if len == 0 {
+            return;
+        }
+        let m_sz = self.mutation_size().min(len);
+        let from = self.prng.gen_range(0, len - m_sz);
+        let mut copy = self.test_case.data[from..from + m_sz].to_vec();
+        // Non-zero xor so the copy always differs from the original
+        let idx = self.prng.gen_range(0, m_sz - 1);
+        copy[idx] ^= self.prng.gen_range(1, 0xff) as u8;
+        let to = from + m_sz;
+        self.test_case.data.splice(to..to, copy);
+        self.test_case.size += m_sz;
+        self.record_mutation(Some(to), m_sz);
+    }
+
+    #[inline(never)]
     fn insert_constants(&mut self) {
+        let mut written = 0;
         // TODO why 10
         for _ in 0..10 {
             let magic = self.prng.gen_range(0, 4 - 1);
@@ -414,6 +2065,7 @@ impl MutationEngine {
                     let val = self.prng.choose(&MAGIC_8);
                     let to = self.prng.gen_range(0, self.test_case.size - 1);
                     self.test_case.data[to] = val;
+                    written += 1;
                 }
                 1 => {
                     let val = self.prng.choose(&MAGIC_16);
@@ -423,6 +2075,7 @@ impl MutationEngine {
                         self.test_case.data[to + i] =
                             ((val >> (8 * (val_sz - (i + 1)))) & 0xff) as u8;
                     }
+                    written += val_sz;
                 }
                 2 => {
                     let val = self.prng.choose(&MAGIC_32);
@@ -432,6 +2085,7 @@ impl MutationEngine {
                         self.test_case.data[to + i] =
                             ((val >> (8 * (val_sz - (i + 1)))) & 0xff) as u8;
                     }
+                    written += val_sz;
                 }
                 3 => {
                     let val = self.prng.choose(&MAGIC_64);
@@ -441,18 +2095,22 @@ impl MutationEngine {
                         self.test_case.data[to + i] =
                             ((val >> (8 * (val_sz - (i + 1)))) & 0xff) as u8;
                     }
+                    written += val_sz;
                 }
                 _ => {
                     unreachable!()
                 }
             };
         }
+        self.record_mutation(None, written);
     }
 
     fn truncate(&mut self) {
-        let trunc = (self.prng.gen_range(0, 50)) as f64;
-        let t = self.test_case.size - self.test_case.size * ((trunc * 0.01) as usize);
+        let trunc = self.prng.gen_range(0, 50);
+        let t = self.test_case.size - self.test_case.size * trunc / 100;
         self.test_case.data.truncate(t);
+        self.record_mutation(Some(t), self.test_case.size - t);
+        self.test_case.size = t;
     }
 
     fn append(&mut self) {
@@ -460,37 +2118,589 @@ impl MutationEngine {
         let from = self.prng.gen_range(0, self.test_case.size - m_sz);
         let mut slice = vec![0u8; m_sz];
         slice.copy_from_slice(&self.test_case.data[from..from + m_sz]);
+        self.record_mutation(Some(self.test_case.data.len()), m_sz);
         self.test_case.data.append(&mut slice);
         self.test_case.size += m_sz;
     }
 
+    fn insert_magic_sequence(&mut self) {
+        let magic = match &self.format_magic {
+            Some(m) => m.clone(),
+            None => {
+                // Without a known format, insert a random magic value in front of the input
+                let magic = self.prng.choose(&MAGIC_32).to_be_bytes();
+                self.test_case.data.splice(0..0, magic);
+                self.test_case.size += magic.len();
+                self.record_mutation(Some(0), magic.len());
+                return;
+            }
+        };
+        if self.test_case.data.len() < magic.len() {
+            self.test_case.resize(magic.len(), 0);
+        }
+        self.test_case
+            .overwrite_at(0, &magic)
+            .expect("test case was grown to fit the magic");
+        self.record_mutation(Some(0), magic.len());
+    }
+
+    fn pairwise_flip(&mut self) {
+        if self.test_case.size < 2 {
+            return;
+        }
+        let i = self.prng.gen_range(0, self.test_case.size - 1);
+        let mut j = self.prng.gen_range(0, self.test_case.size - 2);
+        if j >= i {
+            j += 1;
+        }
+        let mask = self.prng.gen_range(1, 0xff) as u8;
+        self.test_case.data[i] ^= mask;
+        self.test_case.data[j] ^= mask;
+        self.record_mutation(Some(i), 2);
+    }
+
+    fn replace_byte(&mut self) {
+        let target = self.prng.gen_byte();
+        let replacement = self.prng.gen_byte();
+        if target == replacement || !self.test_case.data.contains(&target) {
+            return;
+        }
+        let mut replaced = 0;
+        self.test_case
+            .data
+            .iter_mut()
+            .filter(|x| **x == target)
+            .for_each(|x| {
+                *x = replacement;
+                replaced += 1;
+            });
+        self.record_mutation(None, replaced);
+    }
+
+    fn null_terminate(&mut self) {
+        let idx = self.prng.gen_range(0, self.test_case.size);
+        if self.test_case.data.get(idx) == Some(&0) {
+            return;
+        }
+        self.test_case.data.insert(idx, 0);
+        self.test_case.size += 1;
+        self.record_mutation(Some(idx), 1);
+    }
+
+    fn expand_run_length(&mut self) {
+        let runs = byte_runs(&self.test_case.data);
+        if runs.is_empty() {
+            return;
+        }
+        let (start, _) = self.prng.choose(&runs);
+        let count = self.prng.gen_range(1, 64);
+        let val = self.test_case.data[start];
+        self.test_case
+            .data
+            .splice(start..start, std::iter::repeat_n(val, count));
+        self.test_case.size += count;
+        self.record_mutation(Some(start), count);
+    }
+
+    fn contract_run_length(&mut self) {
+        let runs = byte_runs(&self.test_case.data);
+        if runs.is_empty() {
+            return;
+        }
+        let (start, len) = self.prng.choose(&runs);
+        self.test_case.data.drain(start..start + len / 2);
+        self.test_case.size -= len / 2;
+        self.record_mutation(Some(start), len / 2);
+    }
+
+    fn reverse_bytes(&mut self) {
+        self.test_case.data.reverse();
+        self.record_mutation(Some(0), self.test_case.data.len());
+    }
+
+    fn reverse_range(&mut self) {
+        let m_sz = self.mutation_size().min(self.test_case.size);
+        let start = self.prng.gen_range(0, self.test_case.size - m_sz);
+        self.test_case.data[start..start + m_sz].reverse();
+        self.record_mutation(Some(start), m_sz);
+    }
+
+    fn chop_and_join(&mut self) {
+        if self.test_case.size < 3 {
+            return;
+        }
+        // Keep at least one byte on either side so the range is strictly interior
+        let a = self.prng.gen_range(1, self.test_case.size - 2);
+        let b = self.prng.gen_range(a + 1, self.test_case.size - 1);
+        self.test_case.data.drain(a..b);
+        self.test_case.size -= b - a;
+        self.record_mutation(Some(a), b - a);
+    }
+
+    // Meta-mutator: runs another enabled mutator with mutation_size() scaled up 2x or 4x
+    fn increase_mutation(&mut self) {
+        let others: Vec<Mutator> = self
+            .mutators
+            .iter()
+            .filter(|&m| *m != Mutator::IncreaseMutation && self.fits(m))
+            .cloned()
+            .collect();
+        if others.is_empty() {
+            return;
+        }
+        self.mutation_multiplier = if self.prng.bool() { 2.0 } else { 4.0 };
+        self.mutator = others[self.prng.rand() % others.len()].clone();
+        self.apply_mutator();
+        self.mutation_multiplier = 1.0;
+        self.mutator = Mutator::IncreaseMutation;
+    }
+
+    fn random_walk(&mut self) {
+        let idx = self.prng.gen_range(0, self.test_case.size - 1);
+        let steps = self.prng.gen_range(1, 20);
+        let mut val = self.test_case.data[idx];
+        for _ in 0..steps {
+            val = if self.prng.bool() {
+                val.wrapping_add(1)
+            } else {
+                val.wrapping_sub(1)
+            };
+        }
+        self.test_case.data[idx] = val;
+        self.record_mutation(Some(idx), 1);
+    }
+
+    fn high_entropy_insert(&mut self) {
+        let m_sz = self.mutation_size();
+        let idx = self.prng.gen_range(0, self.test_case.data.len());
+        let mut burst = Vec::with_capacity(m_sz);
+        self.prng.fill_bytes(&mut burst, m_sz);
+        self.test_case.data.splice(idx..idx, burst);
+        self.test_case.size += m_sz;
+        self.record_mutation(Some(idx), m_sz);
+    }
+
+    fn corrupt_magic_bytes(&mut self) {
+        let n = self
+            .format_magic
+            .as_ref()
+            .map_or(4, |m| m.len())
+            .min(self.test_case.data.len());
+        let bad = self.prng.choose(&MAGIC_32).to_be_bytes();
+        for (dst, src) in self.test_case.data[..n].iter_mut().zip(bad.iter().cycle()) {
+            *dst = *src;
+        }
+        self.record_mutation(Some(0), n);
+    }
+
+    fn field_boundary_mutation(&mut self) {
+        let len = self.test_case.data.len();
+        if len == 0 {
+            return;
+        }
+        let idx = match self.prng.gen_range(0, 2) {
+            0 => 0,
+            1 => len - 1,
+            // The middle of a buffer shorter than three bytes is its first byte
+            _ if len < 3 => 0,
+            _ => len / 2,
+        };
+        self.test_case.data[idx] = self.prng.gen_byte();
+        self.record_mutation(Some(idx), 1);
+    }
+
+    fn insert_line_terminator(&mut self) {
+        let terminator: &[u8] = match self.prng.gen_range(0, 2) {
+            0 => b"\n",
+            1 => b"\r\n",
+            _ => b"\r",
+        };
+        let idx = self.prng.gen_range(0, self.test_case.data.len());
+        self.test_case
+            .data
+            .splice(idx..idx, terminator.iter().copied());
+        self.test_case.size += terminator.len();
+        self.record_mutation(Some(idx), terminator.len());
+    }
+
+    fn remove_line_terminator(&mut self) {
+        let lf = match self.test_case.find_first(b"\n") {
+            Some(lf) => lf,
+            None => return,
+        };
+        let start = if lf > 0 && self.test_case.data[lf - 1] == b'\r' {
+            lf - 1
+        } else {
+            lf
+        };
+        self.test_case.data.drain(start..=lf);
+        self.test_case.size = self.test_case.data.len();
+        self.record_mutation(Some(start), lf + 1 - start);
+    }
+
+    fn pad_to_alignment(&mut self) {
+        let len = self.test_case.data.len();
+        let pad = (self.alignment - len % self.alignment) % self.alignment;
+        self.test_case.resize(len + pad, 0);
+        self.record_mutation(Some(len), pad);
+    }
+
+    fn end_of_input(&mut self) {
+        let len = self.test_case.data.len();
+        // Usually at the end, sometimes mid-stream to cut the input short
+        let idx = if self.prng.bool() {
+            len
+        } else {
+            self.prng.gen_range(0, len)
+        };
+        let marker = self.eof_marker.clone();
+        let m_sz = marker.len();
+        self.test_case.data.splice(idx..idx, marker);
+        self.test_case.size = self.test_case.data.len();
+        self.record_mutation(Some(idx), m_sz);
+    }
+
+    fn bit_flip_range(&mut self) {
+        let m_sz = self.mutation_size().min(self.test_case.size);
+        let start = self.prng.gen_range(0, self.test_case.size - m_sz);
+        self.test_case.data[start..start + m_sz]
+            .iter_mut()
+            .for_each(|b| *b ^= 0xff);
+        self.record_mutation(Some(start), m_sz);
+    }
+
+    fn upper_lower_toggle(&mut self) {
+        let m_sz = self.mutation_size();
+        let mut toggled = 0;
+        let mut skipped = 0;
+        // Non-letters don't count; give up once misses outnumber the buffer length
+        while toggled < m_sz && skipped <= self.test_case.data.len() {
+            let idx = self.prng.gen_range(0, self.test_case.size - 1);
+            if self.test_case.data[idx].is_ascii_alphabetic() {
+                self.test_case.data[idx] ^= 0x20;
+                toggled += 1;
+            } else {
+                skipped += 1;
+            }
+        }
+        self.record_mutation(None, toggled);
+    }
+
+    fn insert_crlf(&mut self) {
+        let idx = self.prng.gen_range(0, self.test_case.data.len());
+        self.test_case.data.splice(idx..idx, *b"\r\n");
+        self.test_case.size += 2;
+        self.record_mutation(Some(idx), 2);
+    }
+
+    fn normalize_line_endings(&mut self) {
+        let to_crlf = self.prng.bool();
+        let data = &self.test_case.data;
+        let mut out = Vec::with_capacity(data.len());
+        let mut replaced = 0;
+        let mut i = 0;
+        while i < data.len() {
+            let crlf = data[i] == b'\r' && data.get(i + 1) == Some(&b'\n');
+            if crlf && !to_crlf {
+                out.push(b'\n');
+                replaced += 1;
+                i += 2;
+                continue;
+            }
+            if crlf {
+                out.extend_from_slice(b"\r\n");
+                i += 2;
+                continue;
+            }
+            if data[i] == b'\n' && to_crlf {
+                out.extend_from_slice(b"\r\n");
+                replaced += 1;
+            } else {
+                out.push(data[i]);
+            }
+            i += 1;
+        }
+        self.test_case.size = out.len();
+        self.test_case.data = out;
+        self.record_mutation(None, replaced);
+    }
+
+    fn full_randomize(&mut self) {
+        let size = self.test_case.size;
+        self.test_case.data.clear();
+        self.prng.fill_bytes(&mut self.test_case.data, size);
+        self.record_mutation(Some(0), size);
+    }
+
+    fn random_splice(&mut self) {
+        if self.test_case.size < 2 {
+            return;
+        }
+        let m_sz = self.mutation_size().min(self.test_case.size / 2);
+        // Two non-overlapping ranges of the same length, copied in a random direction
+        let a = self.prng.gen_range(0, self.test_case.size - 2 * m_sz);
+        let b = self.prng.gen_range(a + m_sz, self.test_case.size - m_sz);
+        let (src, dst) = if self.prng.bool() { (a, b) } else { (b, a) };
+        self.test_case.data.copy_within(src..src + m_sz, dst);
+        self.record_mutation(Some(dst), m_sz);
+    }
+
     fn splice(&mut self) {
         let split_idx = self.prng.gen_range(0, self.test_case.size - 1);
         let pick = self.prng.rand() % self.corpus.as_ref().unwrap().len();
-        let splice_tc = &self.corpus.as_mut().unwrap()[pick];
-        let splice_idx = self.prng.gen_range(0, splice_tc.len() - 1);
+        let splice_len = self.corpus.as_ref().unwrap()[pick].len();
+        let splice_idx = self.prng.gen_range(0, splice_len - 1);
+        self.splice_at(split_idx, pick, splice_idx);
+    }
+
+    fn splice_at(&mut self, split_idx: usize, pick: usize, splice_idx: usize) {
+        let splice_tc = &self.corpus.as_ref().unwrap()[pick];
+        let spliced = splice_tc.len() - splice_idx;
         self.test_case.data =
             [&self.test_case.data[..split_idx], &splice_tc[splice_idx..]].concat();
+        self.test_case.size = self.test_case.data.len();
+        self.record_mutation(Some(split_idx), spliced);
+    }
+
+    fn interleave_corpus(&mut self) {
+        let corpus = self.corpus.as_ref().unwrap();
+        // An entry is only interleaved with itself when it is the only one
+        let (a, b) = if corpus.len() > 1 {
+            let picks = self.prng.sample_slice_indices(corpus.len(), 2);
+            (&corpus[picks[0]], &corpus[picks[1]])
+        } else {
+            (&corpus[0], &corpus[0])
+        };
+        let mut data = Vec::with_capacity(a.len() + b.len());
+        for (x, y) in a.iter().zip(b.iter()) {
+            data.push(*x);
+            data.push(*y);
+        }
+        let common = a.len().min(b.len());
+        data.extend_from_slice(&a[common..]);
+        data.extend_from_slice(&b[common..]);
+        self.test_case.size = data.len();
+        self.test_case.data = data;
+        self.record_mutation(Some(0), self.test_case.size);
+    }
+
+    fn truncate_and_append(&mut self) {
+        // Always drop at least one byte so this never degrades to a plain append
+        let t = self
+            .prng
+            .gen_range(0, self.test_case.data.len().saturating_sub(1));
+        self.test_case.data.truncate(t);
+        let corpus = self.corpus.as_ref().unwrap();
+        let pick = &corpus[self.prng.rand() % corpus.len()];
+        let from = self.prng.gen_range(0, pick.len().saturating_sub(1));
+        self.test_case.data.extend_from_slice(&pick[from..]);
+        self.test_case.size = self.test_case.data.len();
+        self.record_mutation(Some(t), self.test_case.size - t);
+    }
+
+    fn flood_with_pattern(&mut self) {
+        let corpus = self.corpus.as_ref().unwrap();
+        let pick = &corpus[self.prng.rand() % corpus.len()];
+        if pick.is_empty() || self.test_case.size == 0 {
+            return;
+        }
+        let pattern_len = self.prng.gen_range(1, pick.len().min(8));
+        let pattern = pick[..pattern_len].to_vec();
+        let len = (self.mutation_size() * pattern_len).min(self.test_case.size);
+        let start = self.prng.gen_range(0, self.test_case.size - len);
+        self.test_case.data[start..start + len]
+            .iter_mut()
+            .zip(pattern.iter().cycle())
+            .for_each(|(x, p)| *x = *p);
+        self.record_mutation(Some(start), len);
+    }
+
+    fn swap_dict_tokens(&mut self) {
+        let token_dict = self.token_dict.as_ref().unwrap();
+        if token_dict.len() < 2 {
+            return;
+        }
+        let matches: Vec<(usize, usize)> = token_dict
+            .iter()
+            .enumerate()
+            .flat_map(|(t, token)| {
+                self.test_case
+                    .windows_containing(token)
+                    .into_iter()
+                    .map(move |pos| (pos, t))
+            })
+            .collect();
+        if matches.is_empty() {
+            return;
+        }
+        let (pos, found) = matches[self.prng.rand() % matches.len()];
+        let mut pick = self.prng.rand() % (token_dict.len() - 1);
+        if pick >= found {
+            pick += 1;
+        }
+        // Overwrite the match in place: truncate a longer replacement, pad a
+        // shorter one with spaces so the buffer length never changes
+        let len = token_dict[found].len();
+        let replacement = token_dict[pick]
+            .iter()
+            .copied()
+            .chain(std::iter::repeat(b' '));
+        self.test_case.data[pos..pos + len]
+            .iter_mut()
+            .zip(replacement)
+            .for_each(|(x, r)| *x = r);
+        self.record_mutation(Some(pos), len);
+    }
+
+    fn protocol_keyword(&mut self) {
+        let data = &self.test_case.data;
+        if !data
+            .iter()
+            .all(|b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+        {
+            self.insert_from_dict();
+            return;
+        }
+        // Prefer the start of a word or line; `\r\n` is covered by the byte after `\n`
+        let mut boundaries = vec![0];
+        boundaries.extend(
+            data.iter()
+                .enumerate()
+                .filter(|(_, &b)| b == b'\n' || b == b' ')
+                .map(|(i, _)| i + 1),
+        );
+        let idx = boundaries[self.prng.rand() % boundaries.len()];
+        let token_dict = self.token_dict.as_ref().unwrap();
+        let token = &token_dict[self.prng.rand() % token_dict.len()];
+        let len = token.len();
+        self.test_case.data.splice(idx..idx, token.iter().copied());
+        self.test_case.size += len;
+        self.record_mutation(Some(idx), len);
+    }
+
+    fn delete_token(&mut self) {
+        let token_dict = self.token_dict.as_ref().unwrap();
+        let token = &token_dict[self.prng.rand() % token_dict.len()];
+        let positions = self.test_case.windows_containing(token);
+        if positions.is_empty() {
+            return;
+        }
+        let pos = positions[self.prng.rand() % positions.len()];
+        let len = token.len();
+        self.test_case.data.drain(pos..pos + len);
+        self.test_case.size -= len;
+        self.record_mutation(Some(pos), len);
     }
 
     fn insert_from_dict(&mut self) {
-        let token_dict = self.token_dict.as_mut().unwrap();
+        let token_dict = self.token_dict.as_ref().unwrap();
+        let size = self.test_case.data.len();
+        // Tokens are written in place, so only those that fit the input are candidates
+        let fitting: Vec<&Vec<u8>> = token_dict.iter().filter(|t| t.len() <= size).collect();
+        if fitting.is_empty() {
+            return;
+        }
+        let mut written = 0;
         // TODO why 10
         for _ in 0..10 {
-            let pick = self.prng.rand() % token_dict.len();
-            let d_ele = &token_dict[pick];
+            let d_ele = fitting[self.prng.rand() % fitting.len()];
             let d_ele_len = d_ele.len();
-            let ele_as_chrs = d_ele.as_bytes();
+            let idx = self.prng.gen_range(0, size - d_ele_len);
+            self.test_case.data[idx..(d_ele_len + idx)].copy_from_slice(d_ele);
+            written += d_ele_len;
+        }
+        self.record_mutation(None, written);
+    }
+}
+
+impl fmt::Display for MutationEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MutationEngine {{ seed: {:#x}, corpus: {} entries, dict: {} tokens, mutations: {}, mutators: [",
+            self.prng.initial_seed(),
+            self.corpus_len(),
+            self.dict_len(),
+            self.total_mutations
+        )?;
+        for (i, mutator) in self.mutators.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{mutator}")?;
+        }
+        f.write_str("] }")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MutationEngineBuilder {
+    test_case: Option<TestCase>,
+    seed: Option<usize>,
+    dictionary: Option<Vec<String>>,
+    corpus: Option<Arc<Vec<Vec<u8>>>>,
+    format_magic: Option<Vec<u8>>,
+    target_format: Option<TargetFormat>,
+}
+
+impl MutationEngineBuilder {
+    pub fn test_case(mut self, test_case: TestCase) -> Self {
+        self.test_case = Some(test_case);
+        self
+    }
+
+    pub fn seed(mut self, seed: usize) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn dictionary(mut self, dictionary: Vec<String>) -> Self {
+        self.dictionary = Some(dictionary);
+        self
+    }
+
+    pub fn corpus(mut self, corpus: Arc<Vec<Vec<u8>>>) -> Self {
+        self.corpus = Some(corpus);
+        self
+    }
+
+    pub fn format_magic(mut self, magic: Vec<u8>) -> Self {
+        self.format_magic = Some(magic);
+        self
+    }
+
+    pub fn target_format(mut self, fmt: TargetFormat) -> Self {
+        self.target_format = Some(fmt);
+        self
+    }
 
-            let idx = self.prng.gen_range(0, self.test_case.size - d_ele_len);
-            self.test_case.data[idx..(d_ele_len + idx)].clone_from_slice(&ele_as_chrs[..d_ele_len]);
+    pub fn build(self) -> MutationEngine {
+        let mut engine =
+            MutationEngine::new(self.test_case, self.seed, self.dictionary, self.corpus);
+        if let Some(magic) = self.format_magic {
+            engine.set_format_magic(magic);
         }
+        engine.target_format = self.target_format;
+        engine
+    }
+}
+
+impl From<MutationEngine> for TestCase {
+    fn from(engine: MutationEngine) -> Self {
+        engine.test_case
+    }
+}
+
+impl From<&MutationEngine> for TestCase {
+    fn from(engine: &MutationEngine) -> Self {
+        engine.test_case.clone()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::{any, prop, prop_assert, prop_assert_eq, proptest};
 
     #[test]
     fn it_works() {
@@ -502,12 +2712,2070 @@ mod tests {
             .to_vec(),
         );
         let init_tc = TestCase::new(&corpus[0]);
-        let mut mutation_engine = MutationEngine::new(Some(init_tc), None, None, Some(corpus));
+        let mut mutation_engine =
+            MutationEngine::new(Some(init_tc), Some(0x31337), None, Some(corpus));
         let tc = mutation_engine.mutate();
-        println!("Mutation: {:?}", String::from_utf8_lossy(&tc.data));
+        println!("Mutation: {:?}", String::from_utf8_lossy(tc));
 
         let expected = "ThisIsSomeTest".to_string();
-        let actual = String::from_utf8_lossy(&tc.data);
+        let actual = String::from_utf8_lossy(tc);
         assert_ne!(expected, actual);
     }
+
+    #[test]
+    fn from_hex_string_decodes_pairs() {
+        let tc = TestCase::from_hex_string("deadBEEF0102").unwrap();
+        assert_eq!(tc.data, [0xde, 0xad, 0xbe, 0xef, 0x01, 0x02]);
+        assert_eq!(tc.size, 6);
+        assert!(TestCase::from_hex_string("").unwrap().data.is_empty());
+        assert_eq!(
+            TestCase::from_hex_string("abc").unwrap_err(),
+            ParseError::OddLength(3)
+        );
+        assert_eq!(
+            TestCase::from_hex_string("0g").unwrap_err(),
+            ParseError::InvalidHexChar { index: 1, ch: 'g' }
+        );
+        assert!(TestCase::from_hex_string("0é0").is_err());
+    }
+
+    #[test]
+    fn to_hex_string_round_trips() {
+        let tc = TestCase::new(&[0xde, 0xad, 0x00, 0x0f]);
+        assert_eq!(tc.to_hex_string(), "dead000f");
+        assert_eq!(
+            TestCase::from_hex_string(&tc.to_hex_string()).unwrap().data,
+            tc.data
+        );
+        assert_eq!(TestCase::new(&[]).to_hex_string(), "");
+    }
+
+    #[test]
+    fn gen_usize_mod_unbiased_covers_upper_half() {
+        // With modulus 3/4 * usize::MAX a biased `rand() % n` hits the lower third twice as often
+        let modulus = usize::MAX / 4 * 3;
+        let mut prng = Rng::new(0x1337);
+        let upper = (0..10_000)
+            .filter(|_| prng.gen_usize_mod_unbiased(modulus) >= modulus / 2)
+            .count();
+        assert!((4_500..5_500).contains(&upper));
+    }
+
+    #[test]
+    fn test_case_builder_validates_sizes() {
+        let tc = TestCase::with_initial_data(b"hello".to_vec())
+            .max_size(8192)
+            .min_size(1)
+            .metadata("source", "corpus/foo.bin")
+            .build()
+            .unwrap();
+        assert_eq!(tc.data, b"hello");
+        assert_eq!(tc.size, 5);
+        assert_eq!(tc.metadata["source"], "corpus/foo.bin");
+
+        assert_eq!(
+            TestCase::with_initial_data(vec![0; 16])
+                .max_size(8)
+                .build()
+                .unwrap_err(),
+            BuildError::TooLarge {
+                size: 16,
+                max_size: 8
+            }
+        );
+        assert_eq!(
+            TestCase::with_initial_data(vec![])
+                .min_size(1)
+                .build()
+                .unwrap_err(),
+            BuildError::TooSmall {
+                size: 0,
+                min_size: 1
+            }
+        );
+        assert_eq!(
+            TestCase::with_initial_data(vec![0; 4])
+                .min_size(8)
+                .max_size(2)
+                .build()
+                .unwrap_err(),
+            BuildError::InvalidBounds {
+                min_size: 8,
+                max_size: 2
+            }
+        );
+    }
+
+    #[test]
+    fn hamming_distance_counts_flipped_bits() {
+        let a = TestCase::new(&[0x00, 0xff, 0x0f]);
+        let b = TestCase::new(&[0x01, 0xff, 0x00, 0xff]);
+        assert_eq!(TestCase::hamming_distance(&a, &a), 0);
+        assert_eq!(TestCase::hamming_distance(&a, &b), 5);
+        assert_eq!(TestCase::hamming_distance(&b, &a), 5);
+    }
+
+    #[test]
+    fn count_byte_and_positions_of() {
+        let tc = TestCase::new(b"abcab");
+        assert_eq!(tc.count_byte(b'z'), 0);
+        assert!(tc.positions_of(b'z').is_empty());
+        assert_eq!(tc.count_byte(b'a'), 2);
+        assert_eq!(tc.positions_of(b'a'), vec![0, 3]);
+        assert_eq!(tc.positions_of(b'b'), vec![1, 4]);
+
+        let tc = TestCase::new(&[7u8; 4]);
+        assert_eq!(tc.count_byte(7), 4);
+        assert_eq!(tc.positions_of(7), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn fingerprint_is_fnv1a() {
+        assert_eq!(TestCase::new(b"").fingerprint(), 0xcbf29ce484222325);
+        assert_eq!(TestCase::new(b"a").fingerprint(), 0xaf63dc4c8601ec8c);
+        assert_eq!(TestCase::new(b"foobar").fingerprint(), 0x85944171f73967e8);
+        assert_ne!(
+            TestCase::new(b"GET / HTTP/1.1").fingerprint(),
+            TestCase::new(b"PUT / HTTP/1.1").fingerprint()
+        );
+    }
+
+    #[test]
+    fn count_zeros_and_nonzeros() {
+        let tc = TestCase::new(b"\0a\0\0b");
+        assert_eq!(tc.count_zeros(), 3);
+        assert_eq!(tc.count_nonzeros(), 2);
+        let tc = TestCase::new(b"");
+        assert_eq!(tc.count_zeros(), 0);
+        assert_eq!(tc.count_nonzeros(), 0);
+    }
+
+    #[test]
+    fn custom_byte_positions_and_ranges() {
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&[0u8; 64])), None, None, None);
+        mutation_engine.set_byte_positions(vec![0x80]);
+        mutation_engine.bit_flip();
+        assert!(mutation_engine
+            .test_case
+            .data
+            .iter()
+            .all(|&b| b == 0 || b == 0x80));
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&[0u8; 64])), None, None, None);
+        mutation_engine.set_byte_ranges(vec![3, 16]);
+        mutation_engine.arithmetic();
+        mutation_engine.swap_with_width();
+        assert_eq!(mutation_engine.test_case.data.len(), 64);
+    }
+
+    #[test]
+    #[should_panic(expected = "Byte ranges must be between 1 and 16 bytes.")]
+    fn set_byte_ranges_rejects_oversized_width() {
+        let mut mutation_engine = MutationEngine::new(None, None, None, None);
+        mutation_engine.set_byte_ranges(vec![2, 32]);
+    }
+
+    #[test]
+    fn windows_containing_finds_all_matches() {
+        let tc = TestCase::new(b"abcabcaab");
+        assert_eq!(tc.windows_containing(b"ab"), vec![0, 3, 7]);
+        assert_eq!(tc.windows_containing(b"aa"), vec![6]);
+        assert!(tc.windows_containing(b"zz").is_empty());
+        assert!(tc.windows_containing(b"").is_empty());
+        assert!(tc.windows_containing(b"abcabcaabx").is_empty());
+        assert_eq!(tc.find_first(b"bc"), Some(1));
+        assert_eq!(tc.find_last(b"bc"), Some(4));
+        assert_eq!(tc.find_first(b"x"), None);
+
+        let tc = TestCase::new(b"aaaa");
+        assert_eq!(tc.windows_containing(b"aa"), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn resize_grows_and_shrinks() {
+        let mut tc = TestCase::new(b"abc");
+        tc.resize(5, 0x41);
+        assert_eq!(tc.data, b"abcAA");
+        assert_eq!(tc.size, 5);
+        tc.resize(2, 0);
+        assert_eq!(tc.data, b"ab");
+        assert_eq!(tc.size, 2);
+        tc.resize(0, 0);
+        assert!(tc.data.is_empty());
+        assert_eq!(tc.size, 0);
+    }
+
+    #[test]
+    fn test_case_from_str() {
+        let tc = TestCase::from("GET / HTTP/1.1");
+        assert_eq!(tc.data, b"GET / HTTP/1.1");
+        assert_eq!(tc.size, 14);
+        let tc: TestCase = "héllo".parse().unwrap();
+        assert_eq!(tc.data, "héllo".as_bytes());
+        let tc: TestCase = "".into();
+        assert!(tc.data.is_empty());
+    }
+
+    #[test]
+    fn apply_xor_key_is_an_involution() {
+        let mut tc = TestCase::new(b"\x00\x00\x00\x00\x00hello");
+        tc.apply_xor_key(b"\x01\x02");
+        assert_eq!(&tc.data[..5], b"\x01\x02\x01\x02\x01");
+        tc.apply_xor_key(b"\x01\x02");
+        assert_eq!(tc.data, b"\x00\x00\x00\x00\x00hello");
+        tc.apply_xor_key(b"");
+        assert_eq!(tc.data, b"\x00\x00\x00\x00\x00hello");
+    }
+
+    #[test]
+    fn write_appends_to_test_case() {
+        use std::io::Write;
+        let mut tc = TestCase::new(b"GET / ");
+        tc.write_all(b"hello").unwrap();
+        assert_eq!(tc.data, b"GET / hello");
+        assert_eq!(tc.size, tc.data.len());
+        writeln!(tc, " {}", 42).unwrap();
+        tc.flush().unwrap();
+        assert_eq!(tc.data, b"GET / hello 42\n");
+        assert_eq!(tc.size, 15);
+    }
+
+    #[test]
+    fn reader_reads_test_case_data() {
+        use std::io::Read;
+        let tc = TestCase::new(b"GET / HTTP/1.1");
+        let mut reader = tc.reader();
+        let mut buf = [0u8; 4];
+        assert_eq!(reader.read(&mut buf).unwrap(), 4);
+        assert_eq!(&buf, b"GET ");
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"/ HTTP/1.1");
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+
+        let mut all = String::new();
+        tc.reader().read_to_string(&mut all).unwrap();
+        assert_eq!(all, "GET / HTTP/1.1");
+    }
+
+    #[test]
+    fn overwrite_at_checks_bounds() {
+        let mut tc = TestCase::new(b"GET / HTTP/1.1");
+        tc.overwrite_at(0, b"PUT").unwrap();
+        assert_eq!(tc.data, b"PUT / HTTP/1.1");
+        tc.overwrite_at(14, b"").unwrap();
+        assert_eq!(
+            tc.overwrite_at(12, b"2.0"),
+            Err(OverwriteError {
+                range: 12..15,
+                size: 14
+            })
+        );
+        assert_eq!(tc.data, b"PUT / HTTP/1.1");
+        assert!(tc.overwrite_at(usize::MAX, b"x").is_err());
+    }
+
+    #[test]
+    fn insert_magic_sequence_restores_format_magic() {
+        let init_tc = TestCase::new(b"\x00\x00\x00\x00IHDR");
+        let mut mutation_engine = MutationEngine::new(Some(init_tc), None, None, None);
+        mutation_engine.set_format_magic(b"\x89PNG".to_vec());
+        mutation_engine.insert_magic_sequence();
+        assert_eq!(mutation_engine.test_case.data, b"\x89PNGIHDR");
+
+        let mut mutation_engine = MutationEngine::new(Some(TestCase::new(b"ab")), None, None, None);
+        mutation_engine.set_format_magic(b"%PDF".to_vec());
+        mutation_engine.insert_magic_sequence();
+        assert_eq!(mutation_engine.test_case.data, b"%PDF");
+        assert_eq!(mutation_engine.test_case.size, 4);
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"IHDR")), Some(0x1337), None, None);
+        mutation_engine.pin_test_case();
+        mutation_engine
+            .set_mutators(vec![Mutator::InsertMagicSequence])
+            .unwrap();
+        let _ = mutation_engine.mutate();
+        let data = &mutation_engine.test_case.data;
+        assert_eq!(data.len(), 8);
+        assert_eq!(mutation_engine.test_case.size, 8);
+        assert!(MAGIC_32.contains(&u32::from_be_bytes(data[..4].try_into().unwrap())));
+        assert_eq!(&data[4..], b"IHDR");
+        assert!(mutation_engine
+            .explain_last_mutation()
+            .starts_with("insert-magic-sequence: inserted 4 bytes at offset 0"));
+    }
+
+    #[test]
+    fn gradient_byte_flip_only_touches_msb_and_lsb() {
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&[0u8; 256])), None, None, None);
+        mutation_engine.gradient_byte_flip();
+        let data = &mutation_engine.test_case.data;
+        assert!(data.iter().all(|&b| b & !0x81 == 0));
+        assert!(data.iter().any(|&b| b != 0));
+    }
+
+    #[test]
+    fn signed_overflow_writes_int_min() {
+        for (width, min) in [
+            (1, i8::MIN.to_be_bytes().to_vec()),
+            (2, i16::MIN.to_be_bytes().to_vec()),
+            (4, i32::MIN.to_be_bytes().to_vec()),
+            (8, i64::MIN.to_be_bytes().to_vec()),
+        ] {
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&[0x55u8; 64])), None, None, None);
+            mutation_engine.set_byte_ranges(vec![width]);
+            mutation_engine.signed_overflow();
+            let data = &mutation_engine.test_case.data;
+            let hits = data
+                .chunks(width as usize)
+                .filter(|c| c == &min.as_slice())
+                .count();
+            assert!(hits >= 1);
+            assert!(data
+                .chunks(width as usize)
+                .all(|c| c == min.as_slice() || c.iter().all(|&b| b == 0x55)));
+        }
+    }
+
+    #[test]
+    fn increment_and_decrement_byte_wrap() {
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&[0xff; 32])), Some(0x1337), None, None);
+        mutation_engine.increment_byte();
+        assert!(mutation_engine
+            .test_case
+            .data
+            .iter()
+            .all(|&b| b == 0xff || b == 0x00 || b == 0x01));
+        assert!(mutation_engine.test_case.data.iter().any(|&b| b != 0xff));
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&[0x00; 32])), Some(0x1337), None, None);
+        mutation_engine.decrement_byte();
+        assert!(mutation_engine.test_case.data.contains(&0xff));
+    }
+
+    #[test]
+    fn swap_words_exchanges_disjoint_words() {
+        let data: Vec<u8> = (0..8u8).flat_map(|w| [w; 4]).collect();
+        let mut mutation_engine = MutationEngine::new(Some(TestCase::new(&data)), None, None, None);
+        mutation_engine.swap_words();
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(out.len(), data.len());
+        let mut sorted = out.clone();
+        sorted.sort();
+        assert_eq!(sorted, data);
+        assert_ne!(out, &data);
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"short")), None, None, None);
+        mutation_engine.swap_words();
+        assert_eq!(mutation_engine.test_case.data, b"short");
+    }
+
+    #[test]
+    fn reverse_bytes_reverses_whole_buffer() {
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"abcdef")), None, None, None);
+        mutation_engine.reverse_bytes();
+        assert_eq!(mutation_engine.test_case.data, b"fedcba");
+        assert_eq!(mutation_engine.test_case.size, 6);
+    }
+
+    #[test]
+    fn reverse_range_reverses_a_sub_range() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let mut mutation_engine = MutationEngine::new(Some(TestCase::new(&data)), None, None, None);
+        mutation_engine.reverse_range();
+        let info_len = mutation_engine.mutation_length;
+        let start = mutation_engine.mutation_offset.unwrap();
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(out[..start], data[..start]);
+        assert_eq!(out[start + info_len..], data[start + info_len..]);
+        assert!(out[start..start + info_len]
+            .iter()
+            .eq(data[start..start + info_len].iter().rev()));
+    }
+
+    #[test]
+    fn increase_mutation_scales_mutation_size() {
+        // Unscaled, ByteFlip on 100 bytes flips at most 100 * 10% + 1 = 11 bytes
+        let mut longest = 0;
+        for seed in 1..32 {
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&[0; 100])), Some(seed), None, None);
+            mutation_engine
+                .set_mutators(vec![Mutator::IncreaseMutation, Mutator::ByteFlip])
+                .unwrap();
+            mutation_engine.mutator = Mutator::IncreaseMutation;
+            mutation_engine.apply_mutator();
+            let info = mutation_engine.last_mutation_info().unwrap();
+            assert_eq!(info.mutator, Mutator::IncreaseMutation);
+            assert!((3..=41).contains(&info.mutation_length));
+            longest = longest.max(info.mutation_length);
+            assert_eq!(mutation_engine.mutation_multiplier, 1.0);
+            assert_eq!(mutation_engine.mutator, Mutator::IncreaseMutation);
+        }
+        assert!(longest > 11);
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&[0; 100])), Some(0x1337), None, None);
+        mutation_engine.mutator = Mutator::IncreaseMutation;
+
+        mutation_engine
+            .set_mutators(vec![Mutator::IncreaseMutation])
+            .unwrap();
+        mutation_engine.test_case = TestCase::new(&[0; 100]);
+        mutation_engine.apply_mutator();
+        assert_eq!(mutation_engine.test_case.data, [0; 100]);
+    }
+
+    #[test]
+    fn random_walk_stays_near_original_byte() {
+        for seed in 1..32 {
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&[0x41; 16])), Some(seed), None, None);
+            mutation_engine.random_walk();
+            let idx = mutation_engine.mutation_offset.unwrap();
+            let out = &mutation_engine.test_case.data;
+            assert!(out[idx].abs_diff(0x41) <= 20);
+            assert!(out.iter().enumerate().all(|(i, &b)| i == idx || b == 0x41));
+        }
+    }
+
+    #[test]
+    fn full_randomize_replaces_whole_buffer() {
+        let data = vec![0u8; 64];
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.full_randomize();
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(out.len(), data.len());
+        assert_eq!(mutation_engine.test_case.size, data.len());
+        assert_ne!(*out, data);
+        assert_eq!(mutation_engine.mutation_length, data.len());
+    }
+
+    #[test]
+    fn copy_and_modify_inserts_near_duplicate() {
+        let data: Vec<u8> = (0..100).collect();
+        for seed in 1..16 {
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&data)), Some(seed), None, None);
+            mutation_engine.copy_and_modify();
+            let to = mutation_engine.mutation_offset.unwrap();
+            let len = mutation_engine.mutation_length;
+            let out = &mutation_engine.test_case.data;
+            assert_eq!(out.len(), data.len() + len);
+            assert_eq!(mutation_engine.test_case.size, out.len());
+            assert_eq!(out[..to], data[..to]);
+            assert_eq!(out[to + len..], data[to..]);
+            let original = &data[to - len..to];
+            let copy = &out[to..to + len];
+            let diffs = original.iter().zip(copy).filter(|(a, b)| a != b).count();
+            assert_eq!(diffs, 1);
+        }
+    }
+
+    #[test]
+    fn high_entropy_insert_adds_random_burst() {
+        let data = vec![0u8; 100];
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.high_entropy_insert();
+        let idx = mutation_engine.mutation_offset.unwrap();
+        let len = mutation_engine.mutation_length;
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(out.len(), data.len() + len);
+        assert_eq!(mutation_engine.test_case.size, out.len());
+        assert_eq!(out[..idx], data[..idx]);
+        assert_eq!(out[idx + len..], data[idx..]);
+        assert!(out[idx..idx + len].iter().any(|&b| b != 0));
+    }
+
+    #[test]
+    fn corrupt_magic_bytes_targets_header() {
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"\x89PNG\r\n\x1a\nIHDR")),
+            Some(0x1337),
+            None,
+            None,
+        );
+        mutation_engine.corrupt_magic_bytes();
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(&out[4..], b"\r\n\x1a\nIHDR");
+        assert!(MAGIC_32.iter().any(|m| m.to_be_bytes() == out[..4]));
+        assert_eq!(mutation_engine.mutation_length, 4);
+
+        mutation_engine.set_format_magic(b"%PDF-1.".to_vec());
+        mutation_engine.test_case = TestCase::new(b"%PDF-1.7 body");
+        mutation_engine.corrupt_magic_bytes();
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(&out[7..], b"7 body");
+        assert_eq!(out[..3], out[4..7]);
+
+        mutation_engine.test_case = TestCase::new(b"ab");
+        mutation_engine.corrupt_magic_bytes();
+        assert_eq!(mutation_engine.test_case.data.len(), 2);
+    }
+
+    #[test]
+    fn field_boundary_mutation_hits_first_last_or_middle() {
+        use std::collections::HashSet;
+        let mut seen = HashSet::new();
+        for seed in 1..64 {
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&[0; 9])), Some(seed), None, None);
+            mutation_engine.field_boundary_mutation();
+            let idx = mutation_engine.mutation_offset.unwrap();
+            assert!([0, 4, 8].contains(&idx));
+            seen.insert(idx);
+            let out = &mutation_engine.test_case.data;
+            assert!(out.iter().enumerate().all(|(i, &b)| i == idx || b == 0));
+
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&[0; 2])), Some(seed), None, None);
+            mutation_engine.field_boundary_mutation();
+            assert!(mutation_engine.mutation_offset.unwrap() < 2);
+        }
+        assert_eq!(seen.len(), 3);
+    }
+
+    #[test]
+    fn insert_line_terminator_adds_newline_bytes() {
+        let data = b"GET / HTTP/1.1".to_vec();
+        let mut seen = Vec::new();
+        for seed in 1..64 {
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&data)), Some(seed), None, None);
+            mutation_engine.insert_line_terminator();
+            let idx = mutation_engine.mutation_offset.unwrap();
+            let len = mutation_engine.mutation_length;
+            let out = &mutation_engine.test_case.data;
+            assert_eq!(out.len(), data.len() + len);
+            assert_eq!(mutation_engine.test_case.size, out.len());
+            assert_eq!(out[..idx], data[..idx]);
+            assert_eq!(out[idx + len..], data[idx..]);
+            let terminator = out[idx..idx + len].to_vec();
+            assert!([&b"\n"[..], b"\r\n", b"\r"].contains(&terminator.as_slice()));
+            if !seen.contains(&terminator) {
+                seen.push(terminator);
+            }
+        }
+        assert_eq!(seen.len(), 3);
+    }
+
+    #[test]
+    fn remove_line_terminator_drops_first_newline() {
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"GET / HTTP/1.1\r\nHost: a\r\n")),
+            Some(0x1337),
+            None,
+            None,
+        );
+        mutation_engine.remove_line_terminator();
+        assert_eq!(mutation_engine.test_case.data, b"GET / HTTP/1.1Host: a\r\n");
+        assert_eq!(mutation_engine.mutation_offset, Some(14));
+        assert_eq!(mutation_engine.mutation_length, 2);
+
+        mutation_engine.test_case = TestCase::new(b"\nab\n");
+        mutation_engine.remove_line_terminator();
+        assert_eq!(mutation_engine.test_case.data, b"ab\n");
+        assert_eq!(mutation_engine.test_case.size, 3);
+
+        mutation_engine.test_case = TestCase::new(b"no terminator\r");
+        mutation_engine.remove_line_terminator();
+        assert_eq!(mutation_engine.test_case.data, b"no terminator\r");
+    }
+
+    #[test]
+    fn end_of_input_inserts_eof_marker() {
+        let data = b"GET / HTTP/1.1".to_vec();
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.set_eof_marker(b"\r\n\r\n".to_vec());
+        let mut appended = false;
+        let mut interior = false;
+        for _ in 0..32 {
+            mutation_engine.test_case = TestCase::new(&data);
+            mutation_engine.end_of_input();
+            let idx = mutation_engine.mutation_offset.unwrap();
+            let out = &mutation_engine.test_case.data;
+            assert_eq!(mutation_engine.mutation_length, 4);
+            assert_eq!(mutation_engine.test_case.size, data.len() + 4);
+            assert_eq!(out[..idx], data[..idx]);
+            assert_eq!(&out[idx..idx + 4], b"\r\n\r\n");
+            assert_eq!(out[idx + 4..], data[idx..]);
+            appended |= idx == data.len();
+            interior |= idx < data.len();
+        }
+        assert!(appended && interior);
+
+        let mutation_engine = MutationEngine::new(None, Some(0x1337), None, None);
+        assert_eq!(mutation_engine.eof_marker(), [0x00]);
+    }
+
+    #[test]
+    fn pad_to_alignment_appends_nulls_to_boundary() {
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"ELF\x7f\x02")),
+            Some(0x1337),
+            None,
+            None,
+        );
+        mutation_engine.pad_to_alignment();
+        assert_eq!(mutation_engine.test_case.data, b"ELF\x7f\x02\0\0\0");
+        assert_eq!(mutation_engine.test_case.size, 8);
+        assert_eq!(mutation_engine.mutation_offset, Some(5));
+        assert_eq!(mutation_engine.mutation_length, 3);
+
+        mutation_engine.pad_to_alignment();
+        assert_eq!(mutation_engine.test_case.data.len(), 8);
+
+        mutation_engine.set_alignment(16);
+        mutation_engine.pad_to_alignment();
+        assert_eq!(mutation_engine.test_case.data.len(), 16);
+        assert!(mutation_engine.test_case.data[5..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn bit_flip_range_inverts_contiguous_block() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.bit_flip_range();
+        let start = mutation_engine.mutation_offset.unwrap();
+        let len = mutation_engine.mutation_length;
+        let out = &mutation_engine.test_case.data;
+        assert!(len > 0);
+        assert_eq!(out[..start], data[..start]);
+        assert_eq!(out[start + len..], data[start + len..]);
+        assert!(out[start..start + len]
+            .iter()
+            .zip(&data[start..start + len])
+            .all(|(&a, &b)| a == !b));
+        assert_eq!(Mutator::InvertRange, Mutator::BitFlipRange);
+    }
+
+    #[test]
+    fn upper_lower_toggle_only_changes_letter_case() {
+        let data = b"Content-Length: 42\r\n".to_vec();
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.upper_lower_toggle();
+        let out = &mutation_engine.test_case.data;
+        assert!(mutation_engine.mutation_length > 0);
+        assert!(out.eq_ignore_ascii_case(&data));
+        assert_ne!(out, &data);
+        assert!(out
+            .iter()
+            .zip(&data)
+            .all(|(a, b)| a == b || b.is_ascii_alphabetic()));
+
+        mutation_engine.test_case = TestCase::new(b"0123456789");
+        mutation_engine.upper_lower_toggle();
+        assert_eq!(mutation_engine.test_case.data, b"0123456789");
+        assert_eq!(mutation_engine.mutation_length, 0);
+    }
+
+    #[test]
+    fn insert_crlf_and_normalize_line_endings() {
+        let data = b"GET / HTTP/1.1".to_vec();
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.insert_crlf();
+        let idx = mutation_engine.mutation_offset.unwrap();
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(&out[idx..idx + 2], b"\r\n");
+        assert_eq!([&out[..idx], &out[idx + 2..]].concat(), data);
+        assert_eq!(mutation_engine.test_case.size, data.len() + 2);
+
+        let mixed = b"a\r\nb\nc\rd\r\n".to_vec();
+        let mut outputs = Vec::new();
+        for seed in 1..16 {
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&mixed)), Some(seed), None, None);
+            mutation_engine.normalize_line_endings();
+            let out = mutation_engine.test_case.data.clone();
+            assert_eq!(mutation_engine.test_case.size, out.len());
+            if !outputs.contains(&out) {
+                outputs.push(out);
+            }
+        }
+        outputs.sort();
+        assert_eq!(
+            outputs,
+            vec![b"a\nb\nc\rd\n".to_vec(), b"a\r\nb\r\nc\rd\r\n".to_vec()]
+        );
+    }
+
+    #[test]
+    fn random_splice_copies_within_test_case() {
+        let data: Vec<u8> = (0..200u8).collect();
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.random_splice();
+        let dst = mutation_engine.mutation_offset.unwrap();
+        let len = mutation_engine.mutation_length;
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(out.len(), data.len());
+        assert_eq!(out[..dst], data[..dst]);
+        assert_eq!(out[dst + len..], data[dst + len..]);
+        let src = out[dst] as usize;
+        assert!(src + len <= dst || dst + len <= src);
+        assert_eq!(out[dst..dst + len], data[src..src + len]);
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"A")), Some(0x1337), None, None);
+        mutation_engine.random_splice();
+        assert_eq!(mutation_engine.test_case.data, b"A");
+    }
+
+    #[test]
+    fn chop_and_join_removes_interior_range() {
+        let data: Vec<u8> = (0..64u8).collect();
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.chop_and_join();
+        let a = mutation_engine.mutation_offset.unwrap();
+        let removed = mutation_engine.mutation_length;
+        let out = &mutation_engine.test_case.data;
+        assert!(a >= 1 && removed >= 1);
+        assert_eq!(mutation_engine.test_case.size, 64 - removed);
+        assert_eq!(out.len(), 64 - removed);
+        assert_eq!(out[0], 0);
+        assert_eq!(out[out.len() - 1], 63);
+        assert_eq!(out[..a], data[..a]);
+        assert_eq!(out[a..], data[a + removed..]);
+    }
+
+    #[test]
+    fn pairwise_flip_applies_same_mask_to_two_bytes() {
+        let init_tc = TestCase::new(&[0u8; 16]);
+        let mut mutation_engine = MutationEngine::new(Some(init_tc), Some(0x1337), None, None);
+        mutation_engine.pairwise_flip();
+        let flipped: Vec<u8> = mutation_engine
+            .test_case
+            .data
+            .iter()
+            .copied()
+            .filter(|&b| b != 0)
+            .collect();
+        assert_eq!(flipped.len(), 2);
+        assert_eq!(flipped[0], flipped[1]);
+    }
+
+    #[test]
+    fn replace_byte_replaces_every_occurrence() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1024).collect();
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&data)), Some(0x1337), None, None);
+        mutation_engine.replace_byte();
+        let changed: Vec<usize> = (0..data.len())
+            .filter(|&i| mutation_engine.test_case.data[i] != data[i])
+            .collect();
+        assert_eq!(changed.len(), 4);
+        assert_eq!(mutation_engine.mutation_length, 4);
+        let target = data[changed[0]];
+        assert!(changed.iter().all(|&i| data[i] == target));
+        let replacement = mutation_engine.test_case.data[changed[0]];
+        assert!(changed
+            .iter()
+            .all(|&i| mutation_engine.test_case.data[i] == replacement));
+    }
+
+    #[test]
+    fn null_terminate_inserts_single_null() {
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"hello")), Some(0x1337), None, None);
+        mutation_engine.null_terminate();
+        let idx = mutation_engine.mutation_offset.unwrap();
+        assert_eq!(mutation_engine.test_case.data[idx], 0);
+        assert_eq!(mutation_engine.test_case.size, 6);
+        assert_eq!(mutation_engine.test_case.data.len(), 6);
+        assert_eq!(
+            mutation_engine
+                .test_case
+                .data
+                .iter()
+                .filter(|&&x| x == 0)
+                .count(),
+            1
+        );
+
+        // Positions already holding a null are left alone, only the end gets a new one
+        let mut appended = false;
+        for seed in 1..64 {
+            let mut mutation_engine =
+                MutationEngine::new(Some(TestCase::new(&[0u8; 8])), Some(seed), None, None);
+            mutation_engine.null_terminate();
+            match mutation_engine.mutation_offset {
+                Some(idx) => {
+                    assert_eq!(idx, 8);
+                    assert_eq!(mutation_engine.test_case.data, [0u8; 9]);
+                    assert_eq!(mutation_engine.test_case.size, 9);
+                    appended = true;
+                }
+                None => assert_eq!(mutation_engine.test_case.data, [0u8; 8]),
+            }
+        }
+        assert!(appended);
+    }
+
+    #[test]
+    fn run_length_mutators_resize_runs() {
+        assert_eq!(byte_runs(b"aabcccd"), vec![(0, 2), (3, 3)]);
+        assert!(byte_runs(b"abcd").is_empty());
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"xAAAAy")), None, None, None);
+        mutation_engine.expand_run_length();
+        let data = &mutation_engine.test_case.data;
+        assert!(data.len() > 6 && data.len() <= 6 + 64);
+        assert_eq!(mutation_engine.test_case.size, data.len());
+        assert!(data[1..data.len() - 1].iter().all(|&x| x == b'A'));
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"xAAAAy")), None, None, None);
+        mutation_engine.contract_run_length();
+        assert_eq!(mutation_engine.test_case.data, b"xAAy");
+        assert_eq!(mutation_engine.test_case.size, 4);
+
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"xyz")), None, None, None);
+        mutation_engine.expand_run_length();
+        mutation_engine.contract_run_length();
+        assert_eq!(mutation_engine.test_case.data, b"xyz");
+    }
+
+    #[test]
+    fn interleave_corpus_alternates_bytes() {
+        let corpus = Arc::new(vec![b"abc".to_vec(), b"12345".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        for _ in 0..32 {
+            mutation_engine.interleave_corpus();
+            let data = &mutation_engine.test_case.data;
+            assert_eq!(mutation_engine.test_case.size, data.len());
+            assert!(data.as_slice() == b"a1b2c345" || data.as_slice() == b"1a2b3c45");
+        }
+
+        let corpus = Arc::new(vec![b"abc".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        mutation_engine.interleave_corpus();
+        assert_eq!(mutation_engine.test_case.data, b"aabbcc");
+    }
+
+    #[test]
+    fn fuzz_until_coverage_stable_stops_after_window() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, None, None, Some(corpus));
+        let mut calls = 0;
+        let execs = mutation_engine.fuzz_until_coverage_stable(
+            |_| {
+                calls += 1;
+                if calls <= 5 {
+                    1
+                } else {
+                    0
+                }
+            },
+            10,
+        );
+        assert_eq!(execs, 15);
+        assert_eq!(mutation_engine.fuzz_until_coverage_stable(|_| 0, 0), 0);
+    }
+
+    #[test]
+    fn last_mutation_info_tracks_mutate() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, None, None, Some(corpus));
+        assert!(mutation_engine.last_mutation_info().is_none());
+        for _ in 0..64 {
+            let len = mutation_engine.mutate().len();
+            let info = mutation_engine.last_mutation_info().unwrap();
+            assert_eq!(info.size_before, 14);
+            assert_eq!(info.size_after, len);
+            if let Some(offset) = info.byte_offset {
+                assert!(offset <= info.size_before.max(info.size_after));
+            }
+        }
+
+        let info = MutationInfo {
+            mutator: Mutator::Set,
+            byte_offset: Some(3),
+            mutation_length: 4,
+            size_before: 14,
+            size_after: 14,
+        };
+        assert_eq!(info.to_string(), "set at offset 3 (4 bytes), size 14 -> 14");
+    }
+
+    #[test]
+    fn apply_all_mutators_once_covers_every_mutator() {
+        let corpus = Arc::new(vec![b"YetAnotherSimpleInput".to_vec()]);
+        let token_dict = vec!["foo".to_string()];
+        let init_tc = TestCase::new(b"ThisIsSomeTestInputWithRuns0000");
+        let mut mutation_engine =
+            MutationEngine::new(Some(init_tc), None, Some(token_dict), Some(corpus));
+        let outputs = mutation_engine.apply_all_mutators_once();
+        assert_eq!(outputs.len(), mutation_engine.mutators.len());
+        assert_eq!(
+            mutation_engine.test_case.data,
+            b"ThisIsSomeTestInputWithRuns0000"
+        );
+
+        let tc = TestCase::with_initial_data(b"AB".to_vec())
+            .metadata("source", "seed.bin")
+            .build()
+            .unwrap();
+        let mut mutation_engine = MutationEngine::new(Some(tc), Some(0x1337), None, None);
+        let outputs = mutation_engine.apply_all_mutators_once();
+        let swap = mutation_engine
+            .mutators
+            .iter()
+            .position(|m| *m == Mutator::SwapEndianness)
+            .unwrap();
+        assert_eq!(outputs[swap], b"AB");
+        assert_eq!(mutation_engine.test_case.data, b"AB");
+        assert_eq!(mutation_engine.test_case.metadata["source"], "seed.bin");
+    }
+
+    #[test]
+    fn truncate_and_append_ends_with_corpus_fragment() {
+        let corpus = Arc::new(vec![b"0123456789".to_vec()]);
+        let init_tc = TestCase::new(b"abcdefghij");
+        let mut mutation_engine = MutationEngine::new(Some(init_tc), None, None, Some(corpus));
+        mutation_engine.truncate_and_append();
+        let data = &mutation_engine.test_case.data;
+        assert_eq!(mutation_engine.test_case.size, data.len());
+        let split = data.iter().position(|b| b.is_ascii_digit()).unwrap();
+        assert!(b"abcdefghij".starts_with(&data[..split]));
+        assert!(b"0123456789".ends_with(&data[split..]));
+    }
+
+    #[test]
+    fn truncate_and_append_always_truncates() {
+        let corpus = Arc::new(vec![b"0123456789".to_vec()]);
+        for seed in 1..64 {
+            let mut mutation_engine = MutationEngine::new(
+                Some(TestCase::new(b"abcdefghij")),
+                Some(seed),
+                None,
+                Some(corpus.clone()),
+            );
+            mutation_engine.truncate_and_append();
+            let kept = mutation_engine.mutation_offset.unwrap();
+            assert!(kept < 10);
+            assert_eq!(
+                mutation_engine.test_case.data[..kept],
+                b"abcdefghij"[..kept]
+            );
+        }
+    }
+
+    #[test]
+    fn seed_from_entropy_is_nonzero_and_varies() {
+        let seeds: Vec<usize> = (0..8)
+            .map(|_| MutationEngine::seed_from_entropy())
+            .collect();
+        assert!(seeds.iter().all(|&s| s != 0));
+        assert!(seeds.windows(2).any(|w| w[0] != w[1]));
+        let mut mutation_engine =
+            MutationEngine::new(None, Some(MutationEngine::seed_from_entropy()), None, None);
+        let _tc = mutation_engine.mutate();
+    }
+
+    #[test]
+    fn every_enabled_mutator_is_reachable() {
+        use std::collections::HashSet;
+
+        let token_dict = vec!["foobar".to_string()];
+        let init_tc = TestCase::new(b"ThisIsSomeLongerTestInput");
+        let mut mutation_engine =
+            MutationEngine::new(Some(init_tc), Some(0x1337), Some(token_dict), None);
+        let mut seen = HashSet::new();
+        for num in 0..mutation_engine.mutators.len() {
+            mutation_engine.get_mutator(num);
+            seen.insert(mutation_engine.mutator.clone());
+        }
+        let enabled: HashSet<Mutator> = mutation_engine.mutators.iter().cloned().collect();
+        assert_eq!(seen, enabled);
+        assert!(seen.contains(&Mutator::InsertFromDict));
+        assert!(!seen.contains(&Mutator::Splice));
+    }
+
+    #[test]
+    fn apply_mutation_sequence_is_reproducible() {
+        let seq = [
+            (Mutator::BitFlip, 0x1337),
+            (Mutator::Arithmetic, 0xdead),
+            (Mutator::NullTerminate, 0xbeef),
+        ];
+        let init = b"ThisIsSomeTestInput".to_vec();
+        let mut a = MutationEngine::new(Some(TestCase::new(&init)), None, None, None);
+        let mut b = MutationEngine::new(Some(TestCase::new(&init)), None, None, None);
+        let out = a.apply_mutation_sequence(&seq).unwrap();
+        assert_eq!(out, b.apply_mutation_sequence(&seq).unwrap());
+        assert_ne!(out, init);
+        assert_eq!(
+            a.last_mutation_info().unwrap().mutator,
+            Mutator::NullTerminate
+        );
+
+        let mut c = MutationEngine::new(Some(TestCase::new(&init)), None, None, None);
+        assert_eq!(
+            c.apply_mutation_sequence(&[(Mutator::BitFlip, 0x1337), (Mutator::BitFlip, 0)]),
+            Err(MutationError::ZeroRngState { step: 1 })
+        );
+        assert_eq!(c.test_case.data, init);
+
+        // Shrinking steps leave the test case too short for the ones after them
+        let seq = [
+            (Mutator::Truncate, 0x1337),
+            (Mutator::DeleteRange, 0x1337),
+            (Mutator::ByteFlip, 0x1337),
+        ];
+        let mut d = MutationEngine::new(Some(TestCase::new(b"T")), None, None, None);
+        assert_eq!(
+            d.apply_mutation_sequence(&seq),
+            Err(MutationError::TestCaseTooSmall {
+                size: 0,
+                required: 1
+            })
+        );
+        let mut e = MutationEngine::new(Some(TestCase::new(&init)), None, None, None);
+        let out = e.apply_mutation_sequence(&seq).unwrap();
+        assert_eq!(e.test_case.size, out.len());
+        assert!(out.len() < init.len());
+    }
+
+    #[test]
+    fn flood_with_pattern_tiles_corpus_prefix() {
+        let corpus = Arc::new(vec![b"ABCDEFGHIJ".to_vec()]);
+        let init_tc = TestCase::new(&[0u8; 128]);
+        let mut mutation_engine = MutationEngine::new(Some(init_tc), None, None, Some(corpus));
+        mutation_engine.flood_with_pattern();
+        let start = mutation_engine.mutation_offset.unwrap();
+        let len = mutation_engine.mutation_length;
+        let out = &mutation_engine.test_case.data;
+        assert_eq!(out.len(), 128);
+        assert!(len >= 1);
+        assert_eq!(out[start], b'A');
+        let flooded = &out[start..start + len];
+        assert!(flooded.iter().all(|&b| (b'A'..=b'H').contains(&b)));
+        assert!(out[..start]
+            .iter()
+            .chain(&out[start + len..])
+            .all(|&b| b == 0));
+    }
+
+    #[test]
+    fn corpus_len_counts_entries() {
+        let mutation_engine = MutationEngine::new(None, None, None, None);
+        assert_eq!(mutation_engine.corpus_len(), 0);
+
+        let corpus = Arc::new(vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+        let mutation_engine = MutationEngine::new(None, None, None, Some(corpus));
+        assert_eq!(mutation_engine.corpus_len(), 3);
+    }
+
+    #[test]
+    fn set_and_clear_corpus_toggle_splice() {
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"ThisIsSomeTest")), None, None, None);
+        assert!(!mutation_engine.mutators.contains(&Mutator::Splice));
+
+        let corpus = Arc::new(vec![b"YetAnotherSimpleInput".to_vec()]);
+        assert_eq!(
+            mutation_engine.set_corpus(Arc::new(Vec::new())),
+            Err(MutationError::EmptyCorpus)
+        );
+        assert!(!mutation_engine.mutators.contains(&Mutator::Splice));
+        mutation_engine.set_corpus(corpus.clone()).unwrap();
+        mutation_engine.set_corpus(corpus).unwrap();
+        assert_eq!(mutation_engine.corpus_len(), 1);
+        let splices = mutation_engine
+            .mutators
+            .iter()
+            .filter(|&m| *m == Mutator::Splice)
+            .count();
+        assert_eq!(splices, 1);
+        for _ in 0..32 {
+            let _tc = mutation_engine.mutate();
+        }
+
+        mutation_engine.clear_corpus();
+        assert_eq!(mutation_engine.corpus_len(), 0);
+        assert!(!mutation_engine.mutators.contains(&Mutator::Splice));
+        assert!(!mutation_engine
+            .mutators
+            .contains(&Mutator::InterleaveCorpus));
+    }
+
+    #[test]
+    fn swap_dict_tokens_replaces_known_token() {
+        let token_dict = vec!["GET".to_string(), "PUT".to_string(), "DELETE".to_string()];
+        for seed in 1..32 {
+            let mut mutation_engine = MutationEngine::new(
+                Some(TestCase::new(b"GET / HTTP/1.1")),
+                Some(seed),
+                Some(token_dict.clone()),
+                None,
+            );
+            mutation_engine.swap_dict_tokens();
+            let out = &mutation_engine.test_case.data;
+            assert!(out == b"PUT / HTTP/1.1" || out == b"DEL / HTTP/1.1");
+            assert_eq!(mutation_engine.test_case.size, 14);
+            assert_eq!(out.len(), 14);
+        }
+
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"DELETE /a")),
+            Some(0x1337),
+            Some(vec!["DELETE".to_string(), "GET".to_string()]),
+            None,
+        );
+        mutation_engine.swap_dict_tokens();
+        assert_eq!(mutation_engine.test_case.data, b"GET    /a");
+        assert_eq!(mutation_engine.test_case.size, 9);
+
+        mutation_engine.test_case = TestCase::new(b"no tokens here");
+        mutation_engine.swap_dict_tokens();
+        assert_eq!(mutation_engine.test_case.data, b"no tokens here");
+        mutation_engine
+            .set_dictionary(vec![b"no".to_vec()])
+            .unwrap();
+        mutation_engine.swap_dict_tokens();
+        assert_eq!(mutation_engine.test_case.data, b"no tokens here");
+    }
+
+    #[test]
+    fn protocol_keyword_inserts_at_word_boundaries() {
+        let data = b"GET / HTTP/1.1\r\nHost: a\r\n".to_vec();
+        for seed in 1..32 {
+            let mut mutation_engine = MutationEngine::new(
+                Some(TestCase::new(&data)),
+                Some(seed),
+                Some(vec!["Keep-Alive".to_string()]),
+                None,
+            );
+            mutation_engine.protocol_keyword();
+            let idx = mutation_engine.mutation_offset.unwrap();
+            let out = &mutation_engine.test_case.data;
+            assert!(idx == 0 || data[idx - 1] == b' ' || data[idx - 1] == b'\n');
+            assert_eq!(&out[idx..idx + 10], b"Keep-Alive");
+            assert_eq!(out[..idx], data[..idx]);
+            assert_eq!(out[idx + 10..], data[idx..]);
+            assert_eq!(mutation_engine.test_case.size, out.len());
+        }
+
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(&[0xff; 32])),
+            Some(0x1337),
+            Some(vec!["GET".to_string()]),
+            None,
+        );
+        mutation_engine.protocol_keyword();
+        assert_eq!(mutation_engine.test_case.data.len(), 32);
+        assert!(mutation_engine.test_case.find_first(b"GET").is_some());
+    }
+
+    #[test]
+    fn delete_token_removes_one_occurrence() {
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"a=1&b=2&a=3")),
+            Some(0x1337),
+            Some(vec!["a=".to_string()]),
+            None,
+        );
+        mutation_engine.delete_token();
+        let out = &mutation_engine.test_case.data;
+        assert!(out == b"1&b=2&a=3" || out == b"a=1&b=2&3");
+        assert_eq!(mutation_engine.test_case.size, 9);
+        assert_eq!(mutation_engine.mutation_length, 2);
+
+        mutation_engine.test_case = TestCase::new(b"no match");
+        mutation_engine.delete_token();
+        assert_eq!(mutation_engine.test_case.data, b"no match");
+        assert_eq!(mutation_engine.test_case.size, 8);
+    }
+
+    #[test]
+    fn set_and_clear_dictionary_toggle_insert_from_dict() {
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(b"ThisIsSomeTest")), None, None, None);
+        assert!(!mutation_engine.mutators.contains(&Mutator::InsertFromDict));
+
+        assert_eq!(
+            mutation_engine.set_dictionary(Vec::new()),
+            Err(MutationError::EmptyDictionary)
+        );
+        assert!(!mutation_engine.mutators.contains(&Mutator::InsertFromDict));
+        mutation_engine
+            .set_dictionary(vec![b"\xde\xad".to_vec(), b"GET".to_vec()])
+            .unwrap();
+        mutation_engine
+            .set_dictionary(vec![b"POST".to_vec()])
+            .unwrap();
+        assert_eq!(mutation_engine.dict_len(), 1);
+        assert_eq!(mutation_engine.dict_token(0), Some(&b"POST"[..]));
+        let inserts = mutation_engine
+            .mutators
+            .iter()
+            .filter(|&m| *m == Mutator::InsertFromDict)
+            .count();
+        assert_eq!(inserts, 1);
+
+        mutation_engine.clear_dictionary();
+        assert_eq!(mutation_engine.dict_len(), 0);
+        assert!(!mutation_engine.mutators.contains(&Mutator::InsertFromDict));
+    }
+
+    #[test]
+    fn corpus_bytes_total_sums_entries() {
+        let mutation_engine = MutationEngine::new(None, None, None, None);
+        assert_eq!(mutation_engine.corpus_bytes_total(), 0);
+
+        let corpus = Arc::new(vec![b"ab".to_vec(), b"cde".to_vec(), Vec::new()]);
+        let mutation_engine = MutationEngine::new(None, None, None, Some(corpus));
+        assert_eq!(mutation_engine.corpus_bytes_total(), 5);
+    }
+
+    #[test]
+    fn dict_len_and_dict_token() {
+        let mutation_engine = MutationEngine::new(None, None, None, None);
+        assert_eq!(mutation_engine.dict_len(), 0);
+        assert_eq!(mutation_engine.dict_token(0), None);
+
+        let token_dict = vec!["foobar".to_string(), "deadbeef".to_string()];
+        let mutation_engine = MutationEngine::new(None, None, Some(token_dict), None);
+        assert_eq!(mutation_engine.dict_len(), 2);
+        assert_eq!(mutation_engine.dict_token(1), Some(&b"deadbeef"[..]));
+        assert_eq!(mutation_engine.dict_token(2), None);
+    }
+
+    #[test]
+    fn snapshot_and_restore_roll_back() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        let snap = mutation_engine.snapshot();
+        let first = mutation_engine.mutate().clone();
+        mutation_engine.restore(snap.clone());
+        assert_eq!(mutation_engine.test_case.data, snap.data);
+        assert_eq!(mutation_engine.test_case.size, snap.size);
+        assert_eq!(mutation_engine.mutate(), &first);
+    }
+
+    #[test]
+    fn mutate_splice_at_keeps_prefix() {
+        let corpus = Arc::new(vec![
+            b"GET /index.html HTTP/1.1".to_vec(),
+            b"PUT /upload HTTP/1.1\r\n\r\nbody".to_vec(),
+        ]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        mutation_engine.test_case = TestCase::new(b"GET /index.html HTTP/1.1");
+        let out = mutation_engine.mutate_splice_at(4, 1).unwrap().clone();
+        assert_eq!(out, b"GET /upload HTTP/1.1\r\n\r\nbody");
+        assert_eq!(mutation_engine.test_case.size, out.len());
+        let info = mutation_engine.last_mutation_info().unwrap();
+        assert_eq!(info.mutator, Mutator::Splice);
+        assert_eq!(info.byte_offset, Some(4));
+
+        mutation_engine.test_case = TestCase::new(b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+        assert_eq!(
+            mutation_engine.mutate_splice_at(30, 0).unwrap(),
+            b"0123456789ABCDEFGHIJKLMNOPQRST"
+        );
+    }
+
+    #[test]
+    fn fallible_methods_return_mutation_errors() {
+        let mut prng = Rng::new(0x1337);
+        assert_eq!(
+            prng.try_gen_range(5, 4),
+            Err(MutationError::InvalidRange { min: 5, max: 4 })
+        );
+        assert_eq!(prng.try_gen_range(4, 4), Ok(4));
+
+        let mut mutation_engine =
+            MutationEngine::new(None, Some(0x1337), None, Some(Arc::new(Vec::new())));
+        assert_eq!(
+            mutation_engine.try_mutate(),
+            Err(MutationError::EmptyCorpus)
+        );
+        assert_eq!(
+            mutation_engine.mutate_splice_at(0, 0),
+            Err(MutationError::EmptyCorpus)
+        );
+
+        let corpus = Arc::new(vec![b"A".to_vec(), b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine =
+            MutationEngine::new(None, Some(0x1337), None, Some(corpus.clone()));
+        for _ in 0..2000 {
+            assert!(mutation_engine.try_mutate().is_ok());
+        }
+        let mut with_empty = MutationEngine::new(
+            None,
+            Some(0x1337),
+            None,
+            Some(Arc::new(vec![Vec::new(), b"ThisIsSomeTest".to_vec()])),
+        );
+        assert_eq!(
+            with_empty.try_mutate(),
+            Err(MutationError::TestCaseTooSmall {
+                size: 0,
+                required: 1
+            })
+        );
+        mutation_engine.test_case = TestCase::new(b"ABCD");
+        assert_eq!(
+            mutation_engine.mutate_splice_at(8, 1),
+            Err(MutationError::TestCaseTooSmall {
+                size: 4,
+                required: 8
+            })
+        );
+        assert_eq!(
+            mutation_engine.mutate_splice_at(0, 2),
+            Err(MutationError::CorpusIndexOutOfRange { index: 2, len: 2 })
+        );
+
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine =
+            MutationEngine::new(None, Some(0x1337), Some(Vec::new()), Some(corpus));
+        assert_eq!(
+            mutation_engine.try_mutate(),
+            Err(MutationError::EmptyDictionary)
+        );
+        mutation_engine.clear_dictionary();
+        assert!(mutation_engine.try_mutate().is_ok());
+        assert_eq!(MutationError::EmptyCorpus.to_string(), "corpus is empty");
+    }
+
+    #[test]
+    fn try_mutate_skips_mutators_that_need_longer_inputs() {
+        let corpus = Arc::new(vec![b"abc".to_vec(), b"xyz".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        for _ in 0..2000 {
+            assert!(mutation_engine.try_mutate().is_ok());
+        }
+
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let dict = Some(vec!["A".repeat(30), "GET".to_string()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), dict, Some(corpus));
+        for _ in 0..2000 {
+            assert!(mutation_engine.try_mutate().is_ok());
+        }
+        mutation_engine.test_case = TestCase::new(b"ThisIsSomeTest");
+        mutation_engine.insert_from_dict();
+        let data = &mutation_engine.test_case.data;
+        assert_eq!(data.len(), 14);
+        assert!(data.windows(3).any(|w| w == b"GET"));
+
+        let corpus = Arc::new(vec![b"abc".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        mutation_engine
+            .set_mutators(vec![Mutator::Arithmetic, Mutator::InsertConstants])
+            .unwrap();
+        assert_eq!(
+            mutation_engine.try_mutate(),
+            Err(MutationError::TestCaseTooSmall {
+                size: 3,
+                required: 8
+            })
+        );
+    }
+
+    #[test]
+    fn mutation_count_limit_exhausts_engine() {
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"GET / HTTP/1.1")),
+            Some(0x1337),
+            None,
+            None,
+        );
+        mutation_engine.set_mutation_count_limit(3);
+        for _ in 0..3 {
+            assert!(mutation_engine.try_mutate().is_ok());
+        }
+        let err = mutation_engine.try_mutate().unwrap_err();
+        assert_eq!(err, MutationError::MutationExhausted { limit: 3 });
+        assert_eq!(err.to_string(), "mutation limit of 3 reached");
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _ = mutation_engine.mutate();
+        }));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn fuzz_loops_stop_at_mutation_count_limit() {
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"GET / HTTP/1.1")),
+            Some(0x1337),
+            None,
+            None,
+        );
+        mutation_engine.set_mutation_count_limit(5);
+        // A harness that always reports new coverage would never stabilize on its own
+        assert_eq!(mutation_engine.fuzz_until_coverage_stable(|_| 1, 10), 5);
+
+        mutation_engine.set_mutation_count_limit(8);
+        let (execs, crashes) = mutation_engine.fuzz_with_timeout(|_| {}, Duration::from_secs(60));
+        assert_eq!(execs, 3);
+        assert!(crashes.is_empty());
+    }
+
+    #[test]
+    fn checkpoint_and_reset_resumes_campaign() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        let initial = mutation_engine.test_case.data.clone();
+        let first: Vec<Vec<u8>> = (0..8).map(|_| mutation_engine.mutate().clone()).collect();
+
+        let cp = mutation_engine.checkpoint_and_reset();
+        assert_eq!(cp.total_mutations, 8);
+        assert!(cp.last_info.is_some());
+        assert_eq!(mutation_engine.test_case.data, initial);
+        assert!(mutation_engine.last_mutation_info().is_none());
+        assert!(mutation_engine.corpus.is_some());
+        // A fresh campaign replays the same sequence
+        let replay: Vec<Vec<u8>> = (0..8).map(|_| mutation_engine.mutate().clone()).collect();
+        assert_eq!(replay, first);
+
+        let resumed = {
+            let mut other = MutationEngine::new(
+                None,
+                Some(0x1337),
+                None,
+                Some(Arc::new(vec![b"ThisIsSomeTest".to_vec()])),
+            );
+            for _ in 0..12 {
+                let _ = other.mutate();
+            }
+            other.test_case.data.clone()
+        };
+        let saved = cp.last_info.clone().unwrap();
+        mutation_engine.restore_checkpoint(cp);
+        let info = mutation_engine.last_mutation_info().unwrap();
+        assert_eq!(info.mutator, mutation_engine.mutator);
+        assert_eq!(info.mutator, saved.mutator);
+        assert_eq!(info.byte_offset, mutation_engine.mutation_offset);
+        assert_eq!(info.mutation_length, mutation_engine.mutation_length);
+        for _ in 0..4 {
+            let _ = mutation_engine.mutate();
+        }
+        assert_eq!(mutation_engine.test_case.data, resumed);
+        assert_eq!(mutation_engine.total_mutations, 12);
+    }
+
+    #[test]
+    fn display_summarizes_engine() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec(), b"Another".to_vec()]);
+        let dict = Some(vec!["GET".to_string()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0xdead), dict, Some(corpus));
+        mutation_engine
+            .set_mutators(vec![Mutator::BitFlip, Mutator::InsertFromDict])
+            .unwrap();
+        for _ in 0..3 {
+            let _ = mutation_engine.mutate();
+        }
+        assert_eq!(
+            mutation_engine.to_string(),
+            "MutationEngine { seed: 0xdead, corpus: 2 entries, dict: 1 tokens, mutations: 3, \
+             mutators: [bit-flip, insert-from-dict] }"
+        );
+        assert_eq!(Mutator::Set.to_string(), "set");
+        assert_eq!(Mutator::InsertCRLF.to_string(), "insert-crlf");
+        assert_eq!(Mutator::BitFlipRange.to_string(), "bit-flip-range");
+    }
+
+    #[test]
+    fn fuzz_with_timeout_collects_crashing_inputs() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        let (execs, crashes) = mutation_engine.fuzz_with_timeout(
+            |data| assert!(data.len() != 14 || data[0] == b'T'),
+            Duration::from_millis(50),
+        );
+        assert!(execs > 0);
+        assert!(crashes.len() < execs);
+        assert!(crashes.iter().all(|c| c.len() == 14 && c[0] != b'T'));
+
+        let (execs, crashes) = mutation_engine.fuzz_with_timeout(|_| {}, Duration::from_millis(0));
+        assert_eq!(execs, 0);
+        assert!(crashes.is_empty());
+    }
+
+    #[test]
+    fn explain_last_mutation_describes_info() {
+        let corpus = Arc::new(vec![b"GET /index.html HTTP/1.1".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        assert_eq!(
+            mutation_engine.explain_last_mutation(),
+            "no mutation applied yet"
+        );
+        mutation_engine.test_case = TestCase::new(b"GET /index.html HTTP/1.1");
+        mutation_engine.mutate_splice_at(4, 0).unwrap();
+        assert_eq!(
+            mutation_engine.explain_last_mutation(),
+            "splice: spliced in 20 bytes at offset 4 (size 24 -> 24)"
+        );
+        let _ = mutation_engine.mutate();
+        let explained = mutation_engine.explain_last_mutation();
+        let info = mutation_engine.last_mutation_info().unwrap();
+        assert!(explained.starts_with(&format!("{}: ", info.mutator)));
+
+        // Each of the ten writes is 1, 2, 4 or 8 bytes wide
+        mutation_engine.test_case = TestCase::new(&[0u8; 64]);
+        mutation_engine.mutator = Mutator::InsertConstants;
+        mutation_engine.apply_mutator();
+        let written = mutation_engine
+            .last_mutation_info()
+            .unwrap()
+            .mutation_length;
+        assert!((10..=80).contains(&written));
+        assert_eq!(
+            mutation_engine.explain_last_mutation(),
+            format!("insert-constants: overwrote {written} bytes (size 64 -> 64)")
+        );
+
+        let dict = Some(vec!["GET".to_string()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), dict, None);
+        mutation_engine.test_case = TestCase::new(&[0xff; 16]);
+        mutation_engine.mutator = Mutator::ProtocolKeyword;
+        mutation_engine.apply_mutator();
+        assert_eq!(
+            mutation_engine.explain_last_mutation(),
+            "protocol-keyword: overwrote 30 bytes (size 16 -> 16)"
+        );
+        mutation_engine.test_case = TestCase::new(b"a\r\nb\r\n");
+        mutation_engine.mutator = Mutator::NormalizeLineEndings;
+        mutation_engine.apply_mutator();
+        assert!(mutation_engine
+            .explain_last_mutation()
+            .starts_with("normalize-line-endings: "));
+        let info = mutation_engine.last_mutation_info().unwrap();
+        assert_eq!(
+            info.size_after.abs_diff(info.size_before),
+            info.mutation_length
+        );
+    }
+
+    #[test]
+    fn mutate_and_diff_reports_changed_bytes() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        mutation_engine.set_mutators(vec![Mutator::Set]).unwrap();
+        let diff = mutation_engine.mutate_and_diff();
+        assert_eq!(diff.original, b"ThisIsSomeTest");
+        assert_eq!(diff.mutated, mutation_engine.test_case.data);
+        for (i, (a, b)) in diff.original.iter().zip(diff.mutated.iter()).enumerate() {
+            assert_eq!(a != b, diff.changed_bytes.contains(&i));
+        }
+
+        mutation_engine
+            .set_mutators(vec![Mutator::HighEntropyInsert])
+            .unwrap();
+        let diff = mutation_engine.mutate_and_diff();
+        assert!(diff.mutated.len() > diff.original.len());
+        assert_eq!(diff.changed_bytes.last(), Some(&(diff.mutated.len() - 1)));
+    }
+
+    #[test]
+    fn import_libfuzzer_corpus_is_idempotent() {
+        use std::fs;
+        let dir = std::env::temp_dir().join(format!("hantu-libfuzzer-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("0a1b2c3d4e5f"), b"GET / HTTP/1.1").unwrap();
+        fs::write(dir.join("crash-deadbeef"), b"PUT / HTTP/1.1").unwrap();
+
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, None);
+        assert_eq!(mutation_engine.import_libfuzzer_corpus(&dir).unwrap(), 2);
+        assert_eq!(mutation_engine.corpus_len(), 2);
+        assert!(mutation_engine.mutators.contains(&Mutator::Splice));
+        assert_eq!(mutation_engine.import_libfuzzer_corpus(&dir).unwrap(), 0);
+        assert_eq!(mutation_engine.corpus_len(), 2);
+        let _ = mutation_engine.mutate();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(mutation_engine.import_libfuzzer_corpus(&dir).is_err());
+    }
+
+    #[test]
+    fn export_corpus_writes_afl_queue_files() {
+        use std::fs;
+        let dir = std::env::temp_dir().join(format!("hantu-export-{}", std::process::id()));
+        let mutation_engine = MutationEngine::new(None, Some(0x1337), None, None);
+        assert_eq!(mutation_engine.export_corpus(&dir).unwrap(), 0);
+
+        let corpus = Arc::new(vec![b"GET / HTTP/1.1".to_vec(), b"PUT / HTTP/1.1".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        assert_eq!(mutation_engine.export_corpus(&dir).unwrap(), 2);
+        assert_eq!(
+            fs::read(dir.join("id:000001,src:hantu")).unwrap(),
+            b"PUT / HTTP/1.1"
+        );
+
+        mutation_engine.clear_corpus();
+        assert_eq!(mutation_engine.import_libfuzzer_corpus(&dir).unwrap(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn run_calibration_disables_panicking_mutators() {
+        let tc = TestCase::with_initial_data(b"ABCDEFGHIJKLMNOP".to_vec())
+            .metadata("source", "seed.bin")
+            .build()
+            .unwrap();
+        let mut mutation_engine = MutationEngine::new(Some(tc), Some(0x1337), None, None);
+        let prng_state = mutation_engine.prng.0;
+        let report = mutation_engine.run_calibration(64);
+        assert_eq!(report.iterations, 64);
+        assert_eq!(report.mutators.iter().map(|c| c.applied).sum::<usize>(), 64);
+        assert_eq!(mutation_engine.test_case.data, b"ABCDEFGHIJKLMNOP");
+        assert_eq!(mutation_engine.test_case.metadata["source"], "seed.bin");
+        assert_eq!(mutation_engine.prng.0, prng_state);
+        assert_eq!(mutation_engine.prng.initial_seed(), 0x1337);
+        assert!(mutation_engine.last_mutation_info().is_none());
+
+        // Too short for some mutators, which are skipped rather than disabled
+        mutation_engine.test_case = TestCase::new(b"AB");
+        let enabled = mutation_engine.mutators.len();
+        let report = mutation_engine.run_calibration(512);
+        assert!(report.disabled().is_empty());
+        assert_eq!(mutation_engine.mutators.len(), enabled);
+        let swap = report
+            .mutators
+            .iter()
+            .find(|c| c.mutator == Mutator::SwapEndianness)
+            .unwrap();
+        assert_eq!(swap.applied, 0);
+
+        // A size past the end of the data makes BitFlip index out of bounds
+        mutation_engine.test_case = TestCase::new(b"ABCDEF");
+        mutation_engine.test_case.size = 64;
+        mutation_engine
+            .set_mutators(vec![Mutator::BitFlip, Mutator::PadToAlignment])
+            .unwrap();
+        let report = mutation_engine.run_calibration(16);
+        assert_eq!(report.disabled(), vec![Mutator::BitFlip]);
+        let pad = &report.mutators[1];
+        assert!(pad.applied > 0 && !pad.panicked);
+        assert_eq!(mutation_engine.mutators, vec![Mutator::PadToAlignment]);
+
+        mutation_engine
+            .set_mutators(vec![Mutator::BitFlip])
+            .unwrap();
+        let report = mutation_engine.run_calibration(16);
+        assert_eq!(report.disabled(), vec![Mutator::BitFlip]);
+        assert_eq!(mutation_engine.mutators, vec![Mutator::BitFlip]);
+    }
+
+    #[test]
+    fn set_target_format_biases_mutator_choice() {
+        let corpus = Arc::new(vec![b"GET / HTTP/1.1\r\n".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        assert_eq!(mutation_engine.target_format(), None);
+        mutation_engine
+            .set_mutators(vec![
+                Mutator::Arithmetic,
+                Mutator::InsertLineTerminator,
+                Mutator::Set,
+                Mutator::ByteFlip,
+            ])
+            .unwrap();
+        mutation_engine.set_target_format(TargetFormat::Ascii);
+        assert_eq!(mutation_engine.target_format(), Some(TargetFormat::Ascii));
+        let mut hits = 0;
+        for _ in 0..1000 {
+            let _ = mutation_engine.mutate();
+            if mutation_engine.mutator == Mutator::InsertLineTerminator {
+                hits += 1;
+            }
+        }
+        // 4 / 7 of the weight goes to the favored mutator
+        assert!((500..650).contains(&hits), "{hits}");
+
+        mutation_engine.set_target_format(TargetFormat::Binary);
+        let hits = (0..1000)
+            .filter(|_| {
+                let _ = mutation_engine.mutate();
+                mutation_engine.mutator == Mutator::Arithmetic
+            })
+            .count();
+        assert!((500..650).contains(&hits), "{hits}");
+
+        mutation_engine.clear_target_format();
+        assert_eq!(mutation_engine.target_format(), None);
+    }
+
+    #[test]
+    fn tlv_target_format_fixes_length_fields() {
+        // Second record claims 9 value bytes but only 3 are left
+        let mut data = vec![0x01, 0x02, 0xaa, 0xbb, 0x02, 0x09, 0xcc, 0xdd, 0xee];
+        fix_tlv_lengths(&mut data);
+        assert_eq!(data, [0x01, 0x02, 0xaa, 0xbb, 0x02, 0x03, 0xcc, 0xdd, 0xee]);
+        let mut data = vec![0x01, 0x00, 0x02, 0xff, 0x00];
+        fix_tlv_lengths(&mut data);
+        assert_eq!(data, [0x01, 0x00, 0x02, 0x01, 0x00]);
+
+        let record = [&[0x10, 0x20][..], &[0x41; 0x20]].concat();
+        let mut mutation_engine =
+            MutationEngine::new(Some(TestCase::new(&record)), Some(0x1337), None, None);
+        mutation_engine.set_target_format(TargetFormat::Tlv);
+        mutation_engine
+            .set_mutators(vec![Mutator::DeleteRange])
+            .unwrap();
+        mutation_engine.pin_test_case();
+        let _ = mutation_engine.mutate();
+        let out = &mutation_engine.test_case.data;
+        assert!(out.len() < record.len());
+        assert_eq!(out[1] as usize, out.len() - 2);
+
+        let corpus = Arc::new(vec![vec![0x99; 8]]);
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(&record)),
+            Some(0x1337),
+            None,
+            Some(corpus),
+        );
+        mutation_engine.set_target_format(TargetFormat::Tlv);
+        let out = mutation_engine.mutate_splice_at(4, 0).unwrap();
+        assert_eq!(out, &[0x10, 0x06, 0x41, 0x41, 0x99, 0x99, 0x99, 0x99]);
+    }
+
+    #[test]
+    fn pinned_test_case_accumulates_mutations() {
+        let corpus = Arc::new(vec![vec![0u8; 64]]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        mutation_engine.set_mutators(vec![Mutator::Set]).unwrap();
+        mutation_engine.test_case = TestCase::new(&[0xaa; 64]);
+        mutation_engine.pin_test_case();
+        assert!(mutation_engine.is_pinned());
+        for _ in 0..4 {
+            let out = mutation_engine.mutate();
+            assert!(out.contains(&0xaa));
+        }
+
+        mutation_engine.unpin_test_case();
+        assert!(!mutation_engine.is_pinned());
+        let out = mutation_engine.mutate();
+        assert!(out.contains(&0));
+    }
+
+    #[test]
+    fn pinned_test_case_survives_stacked_mutations() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec(), b"GET / HTTP/1.1".to_vec()]);
+        let dict = Some(vec!["GET".to_string(), "\r\n".to_string()]);
+        for seed in 1..9 {
+            let mut mutation_engine =
+                MutationEngine::new(None, Some(seed), dict.clone(), Some(corpus.clone()));
+            mutation_engine.test_case = TestCase::new(b"GET / HTTP/1.1\r\n\r\n");
+            mutation_engine.pin_test_case();
+            for _ in 0..500 {
+                let _ = mutation_engine.mutate();
+                assert_eq!(
+                    mutation_engine.test_case.size,
+                    mutation_engine.test_case.data.len(),
+                    "{}",
+                    mutation_engine.explain_last_mutation()
+                );
+            }
+        }
+
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, None);
+        mutation_engine
+            .set_mutators(vec![
+                Mutator::DeleteRange,
+                Mutator::Truncate,
+                Mutator::BitFlip,
+                Mutator::CopyRange,
+            ])
+            .unwrap();
+        mutation_engine.test_case = TestCase::new(&[0x41; 64]);
+        mutation_engine.pin_test_case();
+        for _ in 0..500 {
+            let _ = mutation_engine.mutate();
+        }
+        assert!(mutation_engine.test_case.data.len() < 64);
+    }
+
+    #[test]
+    fn new_with_builder_configures_engine() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mutation_engine = MutationEngine::new_with_builder()
+            .seed(0x1337)
+            .corpus(corpus.clone())
+            .dictionary(vec!["GET".to_string()])
+            .format_magic(b"\x89PNG".to_vec())
+            .target_format(TargetFormat::Http)
+            .build();
+        assert_eq!(mutation_engine.corpus_len(), 1);
+        assert_eq!(mutation_engine.dict_len(), 1);
+        assert_eq!(mutation_engine.format_magic(), Some(&b"\x89PNG"[..]));
+        assert_eq!(mutation_engine.target_format(), Some(TargetFormat::Http));
+        assert!(mutation_engine.mutators.contains(&Mutator::Splice));
+
+        let mut built = MutationEngine::new_with_builder()
+            .seed(0x1337)
+            .corpus(corpus.clone())
+            .build();
+        let mut direct = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        assert_eq!(built.mutate(), direct.mutate());
+
+        let mutation_engine = MutationEngine::new_with_builder()
+            .test_case(TestCase::new(b"abc"))
+            .build();
+        assert_eq!(mutation_engine.test_case.data, b"abc");
+    }
+
+    #[test]
+    fn set_mutators_validates_list() {
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"ThisIsSomeTest")),
+            Some(0x1337),
+            None,
+            None,
+        );
+        assert_eq!(
+            mutation_engine.set_mutators(Vec::new()),
+            Err(MutationError::NoMutators)
+        );
+        assert_eq!(
+            mutation_engine.set_mutators(vec![Mutator::Set, Mutator::BitFlip, Mutator::Set]),
+            Err(MutationError::DuplicateMutator(Mutator::Set))
+        );
+        assert_eq!(
+            mutation_engine.set_mutators(vec![Mutator::Splice]),
+            Err(MutationError::EmptyCorpus)
+        );
+        assert_eq!(
+            mutation_engine.set_mutators(vec![Mutator::InsertFromDict]),
+            Err(MutationError::EmptyDictionary)
+        );
+        assert!(mutation_engine.effective_mutators().len() > 2);
+
+        mutation_engine
+            .set_mutators(vec![Mutator::Set, Mutator::BitFlip])
+            .unwrap();
+        assert_eq!(
+            mutation_engine.effective_mutators(),
+            &[Mutator::Set, Mutator::BitFlip]
+        );
+        mutation_engine
+            .set_corpus(Arc::new(vec![b"abcd".to_vec()]))
+            .unwrap();
+        mutation_engine.set_mutators(vec![Mutator::Splice]).unwrap();
+    }
+
+    #[test]
+    fn clearing_gated_mutators_keeps_list_non_empty() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(
+            Some(TestCase::new(b"ThisIsSomeTest")),
+            Some(0x1337),
+            None,
+            Some(corpus),
+        );
+        mutation_engine.set_mutators(vec![Mutator::Splice]).unwrap();
+        mutation_engine.clear_corpus();
+        assert_eq!(mutation_engine.effective_mutators(), &BASE_MUTATORS);
+        assert!(mutation_engine.try_mutate().is_ok());
+
+        mutation_engine
+            .set_dictionary(vec![b"GET".to_vec()])
+            .unwrap();
+        mutation_engine
+            .set_mutators(vec![Mutator::InsertFromDict])
+            .unwrap();
+        mutation_engine.clear_dictionary();
+        assert_eq!(mutation_engine.effective_mutators(), &BASE_MUTATORS);
+        assert!(mutation_engine.try_mutate().is_ok());
+
+        // Not reachable through the public API, but must not underflow either
+        mutation_engine.mutators.clear();
+        assert_eq!(mutation_engine.try_mutate(), Err(MutationError::NoMutators));
+    }
+
+    #[test]
+    fn test_case_from_engine() {
+        let corpus = Arc::new(vec![b"ThisIsSomeTest".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        let last = mutation_engine.mutate().clone();
+        let borrowed = TestCase::from(&mutation_engine);
+        assert_eq!(borrowed.data, last);
+        let owned: TestCase = mutation_engine.into();
+        assert_eq!(owned.data, last);
+        assert_eq!(owned.size, borrowed.size);
+    }
+
+    #[test]
+    fn iter_deterministic_is_lazy_over_current_test_case() {
+        let corpus = Arc::new(vec![b"AB".to_vec()]);
+        let mut mutation_engine = MutationEngine::new(None, Some(0x1337), None, Some(corpus));
+        mutation_engine.test_case = TestCase::new(b"AB");
+        let mut iter = mutation_engine.iter_deterministic(DeterministicStage::ByteFlip1);
+        assert_eq!(iter.next(), Some(vec![!b'A', b'B']));
+        assert_eq!(iter.next(), Some(vec![b'A', !b'B']));
+        assert_eq!(iter.next(), None);
+        assert_eq!(
+            mutation_engine
+                .iter_deterministic(DeterministicStage::BitFlip1)
+                .count(),
+            16
+        );
+        assert_eq!(mutation_engine.test_case.data, b"AB");
+    }
+
+    #[test]
+    fn sample_slice_indices_covers_whole_range() {
+        let mut prng = Rng::new(0x1337);
+        let mut all = prng.sample_slice_indices(16, 16);
+        all.sort_unstable();
+        assert_eq!(all, (0..16).collect::<Vec<_>>());
+        assert!(prng.sample_slice_indices(0, 0).is_empty());
+        assert_eq!(prng.sample_slice_indices(1, 1), vec![0]);
+    }
+
+    #[test]
+    fn rng_state_round_trips_through_u64() {
+        let mut prng = Rng::new(0x1337);
+        let state: u64 = Rng::new(0x1337).into();
+        assert_eq!(state, 0x1337);
+        let mut restored = Rng::from(state);
+        for _ in 0..16 {
+            assert_eq!(prng.rand(), restored.rand());
+        }
+
+        let mut zero = Rng::from(0u64);
+        assert_ne!(zero.seed(), 0);
+        assert!((0..4).any(|_| zero.rand() != 0));
+        assert_eq!(Rng::from(0u64).seed(), zero.initial_seed());
+    }
+
+    #[test]
+    fn rng_seed_reports_current_and_initial_state() {
+        let mut prng = Rng::new(0x1337);
+        assert_eq!(prng.seed(), 0x1337);
+        let _ = prng.rand();
+        let _ = prng.rand();
+        assert_ne!(prng.seed(), 0x1337);
+        assert_eq!(prng.initial_seed(), 0x1337);
+
+        let mut resumed = Rng::new(prng.seed());
+        for _ in 0..16 {
+            assert_eq!(prng.rand(), resumed.rand());
+        }
+
+        let timed = Rng::new(0);
+        assert_ne!(timed.initial_seed(), 0);
+        assert_eq!(timed.seed(), timed.initial_seed());
+    }
+
+    #[test]
+    fn gen_bool_weighted_respects_probability() {
+        let mut prng = Rng::new(0x1337);
+        for _ in 0..256 {
+            assert!(!prng.gen_bool_weighted(0.0));
+            assert!(prng.gen_bool_weighted(1.0));
+            assert!(!prng.gen_bool_weighted(-3.0));
+            assert!(prng.gen_bool_weighted(7.5));
+        }
+        let hits = (0..10000).filter(|_| prng.gen_bool_weighted(0.9)).count();
+        assert!((8500..9500).contains(&hits));
+    }
+
+    #[test]
+    fn gen_weighted_index_follows_weights() {
+        let mut prng = Rng::new(0x1337);
+        let mut counts = [0usize; 3];
+        for _ in 0..3000 {
+            counts[prng.gen_weighted_index(&[1, 0, 3])] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!(counts[2] > 2 * counts[0]);
+
+        for _ in 0..32 {
+            assert_eq!(prng.gen_weighted_index(&[0, 0, 7, 0]), 2);
+            assert!(prng.gen_weighted_index(&[0, 0, 0]) < 3);
+        }
+        assert!(prng.gen_weighted_index(&[u32::MAX, u32::MAX, 0]) < 2);
+    }
+
+    proptest! {
+        #[test]
+        fn rng_gen_range_stays_in_bounds(seed in 1usize.., a in any::<usize>(), b in any::<usize>()) {
+            let (min, max) = if a <= b { (a, b) } else { (b, a) };
+            let mut prng = Rng::new(seed);
+            let val = prng.gen_range(min, max);
+            prop_assert!(min <= val && val <= max);
+        }
+
+        #[test]
+        fn rng_gen_usize_mod_unbiased_stays_below_modulus(seed in 1usize.., modulus in 1usize..) {
+            let mut prng = Rng::new(seed);
+            prop_assert!(prng.gen_usize_mod_unbiased(modulus) < modulus);
+        }
+
+        #[test]
+        fn rng_sample_slice_indices_are_distinct(seed in 1usize.., len in 0usize..512, k in 0usize..512) {
+            let k = k.min(len);
+            let mut prng = Rng::new(seed);
+            let mut idx = prng.sample_slice_indices(len, k);
+            prop_assert_eq!(idx.len(), k);
+            prop_assert!(idx.iter().all(|&i| i < len));
+            idx.sort_unstable();
+            idx.dedup();
+            prop_assert_eq!(idx.len(), k);
+        }
+
+        #[test]
+        fn rng_gen_float_is_unit_interval(seed in 1usize..) {
+            let mut prng = Rng::new(seed);
+            let val = prng.gen_float();
+            prop_assert!((0.0..1.0).contains(&val));
+        }
+
+        #[test]
+        fn rng_choose_returns_element(seed in 1usize.., entries in prop::collection::vec(any::<u32>(), 1..64)) {
+            let mut prng = Rng::new(seed);
+            let val = prng.choose(&entries);
+            prop_assert!(entries.contains(&val));
+        }
+
+        #[test]
+        fn rng_fill_bytes_fills_exactly(seed in 1usize.., sz in 0usize..4096) {
+            let mut prng = Rng::new(seed);
+            let mut buf = Vec::new();
+            prng.fill_bytes(&mut buf, sz);
+            prop_assert_eq!(buf.len(), sz);
+        }
+
+        #[test]
+        fn rng_gen_byte_and_bool_cover_their_range(seed in 1usize..) {
+            let mut prng = Rng::new(seed);
+            let mut bytes = [false; 256];
+            let mut flags = [false; 2];
+            for _ in 0..8192 {
+                bytes[prng.gen_byte() as usize] = true;
+                flags[prng.bool() as usize] = true;
+            }
+            prop_assert!(bytes.iter().all(|&seen| seen));
+            prop_assert!(flags[0] && flags[1]);
+        }
+
+        #[test]
+        fn rng_is_deterministic_for_seed(seed in 1usize..) {
+            let mut a = Rng::new(seed);
+            let mut b = Rng::new(seed);
+            for _ in 0..16 {
+                prop_assert_eq!(a.rand(), b.rand());
+            }
+        }
+    }
 }